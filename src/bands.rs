@@ -0,0 +1,817 @@
+//! Horizontal pagination of tables wider than the page into column bands
+
+use crate::Result;
+use crate::TaggedCellHook;
+use crate::drawing::{
+    ImageXObjects, create_new_page, decorate_pages, draw_planned_pages, draw_table_paginated,
+    plan_on_new_pages,
+};
+use crate::error::TableError;
+use crate::grid::{CellGrid, CellPlacement};
+use crate::layout::{TableLayout, cell_text_lines, table_fingerprint};
+use crate::page::{PageBox, Rect};
+use crate::page_break::{PageBreak, PageBreakRows};
+use crate::style::{BandOrder, HorizontalPagination};
+use crate::table::{ColumnWidth, PageFill, Row, Table};
+use crate::{PagedTableResult, PlacedCell, PlacedRow};
+use lopdf::content::Operation;
+use lopdf::{Document, Object, ObjectId};
+use std::collections::HashMap;
+use tracing::debug;
+
+/// Maximum width of a band on `page`: the configured `max_width`, or the
+/// page's width between the table's margins
+pub(crate) fn band_width(table: &Table, page: &PageBox) -> Option<f32> {
+    let config = table.style.horizontal_pagination.as_ref()?;
+    Some(
+        config
+            .max_width
+            .unwrap_or(page.width() - table.style.left_margin - table.style.right_margin),
+    )
+}
+
+/// Whether the table is split into column bands on `page`
+pub(crate) fn is_banded(table: &Table, layout: &TableLayout, page: &PageBox) -> bool {
+    band_width(table, page).is_some_and(|max_width| layout.total_width > max_width)
+}
+
+/// Partition the columns into bands, each starting with the key columns.
+///
+/// Non-key columns are added to a band while it stays within `max_width`;
+/// every band gets at least one non-key column.
+pub(crate) fn column_bands(
+    layout: &TableLayout,
+    config: &HorizontalPagination,
+    max_width: f32,
+) -> Vec<Vec<usize>> {
+    let column_count = layout.column_widths.len();
+    let mut keys: Vec<usize> = config
+        .key_columns
+        .iter()
+        .copied()
+        .filter(|&c| c < column_count)
+        .collect();
+    keys.sort_unstable();
+    keys.dedup();
+    let key_width: f32 = keys.iter().map(|&c| layout.column_widths[c]).sum();
+
+    let mut bands = Vec::new();
+    let mut band: Vec<usize> = Vec::new();
+    let mut band_width = key_width;
+    for col in (0..column_count).filter(|c| !keys.contains(c)) {
+        let width = layout.column_widths[col];
+        if !band.is_empty() && band_width + width > max_width {
+            bands.push(std::mem::take(&mut band));
+            band_width = key_width;
+        }
+        band.push(col);
+        band_width += width;
+    }
+    if !band.is_empty() || bands.is_empty() {
+        bands.push(band);
+    }
+
+    bands
+        .into_iter()
+        .map(|band| {
+            let mut columns: Vec<usize> = keys.iter().copied().chain(band).collect();
+            columns.sort_unstable();
+            columns
+        })
+        .collect()
+}
+
+/// Cells placed in a row that fall in the given columns, with the number of
+/// those columns each covers
+fn covered_placements<'a>(
+    placements: &'a [CellPlacement],
+    columns: &'a [usize],
+) -> impl Iterator<Item = (&'a CellPlacement, usize)> {
+    placements.iter().filter_map(|placement| {
+        let covered = columns
+            .iter()
+            .filter(|&&c| c >= placement.col && c < placement.col + placement.colspan)
+            .count();
+        (covered > 0).then_some((placement, covered))
+    })
+}
+
+/// The cells of a row that fall in the given columns, with colspans clipped to them
+fn project_row(row: &Row, placements: &[CellPlacement], columns: &[usize]) -> Row {
+    let cells = covered_placements(placements, columns)
+        .map(|(placement, covered)| {
+            let mut cell = row.cells[placement.cell].clone();
+            cell.colspan = covered;
+            cell
+        })
+        .collect();
+    Row {
+        cells,
+        style: row.style.clone(),
+        height: row.height,
+        keep_with_next: row.keep_with_next,
+        page_break_before: row.page_break_before,
+    }
+}
+
+/// Project a row that is not part of the table (generated or template row)
+/// spanning all `column_count` columns onto a band
+fn project_generated_row(row: Row, column_count: usize, columns: &[usize]) -> Row {
+    let single = Table::new().add_row(row);
+    match CellGrid::new(&single) {
+        Ok(grid) if grid.columns() == column_count => {
+            project_row(&single.rows[0], grid.placements_in_row(0), columns)
+        }
+        // Leave the mismatch to be reported when the row is laid out
+        _ => single
+            .rows
+            .into_iter()
+            .next()
+            .unwrap_or_else(|| Row::new(Vec::new())),
+    }
+}
+
+/// Standalone table for one band, keeping the full table's row heights.
+///
+/// Page break rows are generated over the full table while the band is drawn,
+/// so the band has none of its own.
+fn band_table(table: &Table, layout: &TableLayout, columns: &[usize]) -> Table {
+    let rows = table
+        .rows
+        .iter()
+        .enumerate()
+        .map(|(row_idx, row)| {
+            project_row(row, layout.grid.placements_in_row(row_idx), columns)
+                .with_height(layout.row_heights[row_idx])
+        })
+        .collect();
+
+    let mut style = table.style.clone();
+    style.horizontal_pagination = None;
+
+    Table {
+        rows,
+        style,
+        column_widths: Some(
+            columns
+                .iter()
+                .map(|&c| ColumnWidth::Pixels(layout.column_widths[c]))
+                .collect(),
+        ),
+        total_width: Some(columns.iter().map(|&c| layout.column_widths[c]).sum()),
+        header_rows: table.header_rows,
+        footer_rows: table.footer_rows,
+        final_footer_rows: table.final_footer_rows,
+        keep_together: table.keep_together.clone(),
+        max_rows_per_page: table.max_rows_per_page,
+        page_fill: table.page_fill.clone().map(|fill| match fill {
+            PageFill::FillerRows(template) => PageFill::FillerRows(project_generated_row(
+                template,
+                layout.column_widths.len(),
+                columns,
+            )),
+            PageFill::StretchLastRow => PageFill::StretchLastRow,
+        }),
+        font_metrics: table.font_metrics.clone(),
+        bold_font_metrics: table.bold_font_metrics.clone(),
+        italic_font_metrics: table.italic_font_metrics.clone(),
+        bold_italic_font_metrics: table.bold_italic_font_metrics.clone(),
+        font_resources: table.font_resources.clone(),
+        #[cfg(feature = "ttf-parser")]
+        embedded_fonts: table.embedded_fonts.clone(),
+        page_break_rows: None,
+        page_factory: table.page_factory.clone(),
+        // Decorated once all bands are drawn, with the total of every band's pages
+        page_decorator: None,
+    }
+}
+
+/// Layout of a band sliced from the full table's layout: the band's column
+/// widths and the full row heights, reusing the wrapped text of every cell
+/// whose columns all fall in the band. Cells clipped to fewer columns are
+/// wrapped again at their narrower width.
+fn band_layout(band: &Table, layout: &TableLayout, columns: &[usize]) -> Result<TableLayout> {
+    let grid = CellGrid::new(band)?;
+    let column_widths: Vec<f32> = columns.iter().map(|&c| layout.column_widths[c]).collect();
+    let text_lines = (0..layout.row_heights.len())
+        .map(|row_idx| {
+            covered_placements(layout.grid.placements_in_row(row_idx), columns)
+                .enumerate()
+                .map(|(band_cell, (placement, covered))| {
+                    if covered == placement.colspan {
+                        return layout.text_lines[row_idx][placement.cell].clone();
+                    }
+                    let band_placement = &grid.placements_in_row(row_idx)[band_cell];
+                    let width = crate::drawing_utils::calculate_cell_width(
+                        band_placement.col,
+                        band_placement.colspan,
+                        &column_widths,
+                    );
+                    cell_text_lines(band, &band.rows[row_idx].cells[band_cell], width)
+                })
+                .collect()
+        })
+        .collect();
+
+    Ok(TableLayout {
+        total_width: column_widths.iter().sum(),
+        total_height: layout.total_height,
+        column_widths,
+        row_heights: layout.row_heights.clone(),
+        grid,
+        text_lines,
+        fingerprint: table_fingerprint(band),
+    })
+}
+
+/// Reports band cells to a tagged-cell hook with their column in the full table
+struct BandHook<'a, 'h> {
+    inner: &'a mut (dyn TaggedCellHook + 'h),
+    columns: &'a [usize],
+}
+
+impl TaggedCellHook for BandHook<'_, '_> {
+    fn begin_cell(&mut self, row: usize, col: usize, is_header: bool) -> Vec<Operation> {
+        self.inner.begin_cell(row, self.columns[col], is_header)
+    }
+
+    fn end_cell(&mut self, row: usize, col: usize, is_header: bool) -> Vec<Operation> {
+        self.inner.end_cell(row, self.columns[col], is_header)
+    }
+}
+
+/// Generates page break rows for the full table and keeps a band's columns
+struct BandPageBreakRows<'a> {
+    inner: &'a dyn PageBreakRows,
+    table: &'a Table,
+    grid: &'a CellGrid,
+    columns: &'a [usize],
+    /// Index of the band's first page among all of the table's pages
+    first_page_index: usize,
+}
+
+impl BandPageBreakRows<'_> {
+    fn project(&self, row: Option<Row>) -> Option<Row> {
+        row.map(|row| project_generated_row(row, self.grid.columns(), self.columns))
+    }
+
+    fn page_break<'b>(&'b self, page_break: &PageBreak<'b>) -> PageBreak<'b> {
+        PageBreak {
+            table: self.table,
+            page_index: self.first_page_index + page_break.page_index,
+            rows_placed: page_break.rows_placed,
+            rows_on_page: page_break.rows_on_page,
+            grid: self.grid,
+        }
+    }
+}
+
+impl PageBreakRows for BandPageBreakRows<'_> {
+    fn carried_forward(&self, page_break: &PageBreak<'_>) -> Option<Row> {
+        self.project(self.inner.carried_forward(&self.page_break(page_break)))
+    }
+
+    fn brought_forward(&self, page_break: &PageBreak<'_>) -> Option<Row> {
+        self.project(self.inner.brought_forward(&self.page_break(page_break)))
+    }
+}
+
+/// Draw a table split into the column bands of its `horizontal_pagination`,
+/// each paginated vertically.
+///
+/// Every band starts at `position`, on `start_page_id` for the first band and
+/// on a new page for the others, so rows line up across bands. Bands starting
+/// on a page from the page factory start at the top of its region instead. With
+/// [`BandOrder::AcrossThenDown`] the pages are then reordered in the page tree.
+/// Bands are projected from the table one at a time, only while drawn.
+pub(crate) fn draw_table_banded(
+    doc: &mut Document,
+    start_page_id: ObjectId,
+    table: &Table,
+    layout: &TableLayout,
+    position: (f32, f32),
+    mut hook: Option<&mut dyn TaggedCellHook>,
+    image_registry: Option<&ImageXObjects>,
+) -> Result<PagedTableResult> {
+    let Some(config) = table.style.horizontal_pagination.as_ref() else {
+        return draw_table_paginated(
+            doc,
+            start_page_id,
+            table,
+            layout,
+            position,
+            hook,
+            image_registry,
+        );
+    };
+    let page = PageBox::of_page(doc, start_page_id)?.visible();
+    let max_width = band_width(table, &page).unwrap_or(layout.total_width);
+    let bands = column_bands(layout, config, max_width);
+    debug!(
+        "Splitting {:.1}pt wide table into {} column bands",
+        layout.total_width,
+        bands.len()
+    );
+
+    let mut band_pages: Vec<Vec<ObjectId>> = Vec::with_capacity(bands.len());
+    // Area covered by the table and region laid out in, for each band's pages
+    let mut band_bounds: Vec<Vec<(Rect, PageBox)>> = Vec::with_capacity(bands.len());
+    let mut split_rows: Vec<usize> = Vec::new();
+    let mut rows: Vec<PlacedRow> = Vec::new();
+    let mut cells: Vec<PlacedCell> = Vec::new();
+    let mut final_position = position;
+    for columns in &bands {
+        // Index of the band's first page among the table's pages
+        let first_page_index: usize = band_pages.iter().map(Vec::len).sum();
+        let band = band_table(table, layout, columns);
+        let band_page_break_rows =
+            table
+                .page_break_rows
+                .as_deref()
+                .map(|inner| BandPageBreakRows {
+                    inner,
+                    table,
+                    grid: &layout.grid,
+                    columns,
+                    first_page_index,
+                });
+        let band_layout = band_layout(&band, layout, columns)?;
+
+        // Bands on a page from the page factory are laid out in its region
+        let (page_id, start_region) = match band_pages.last().and_then(|pages| pages.last()) {
+            Some(&last_page) => match &table.page_factory {
+                Some(factory) => {
+                    let page = factory.continuation_page(doc, last_page, first_page_index)?;
+                    (page.page_id, Some(page.region))
+                }
+                None => (create_new_page(doc, last_page)?, None),
+            },
+            None => (start_page_id, None),
+        };
+
+        let mut band_hook = hook.as_deref_mut().map(|inner| BandHook {
+            inner,
+            columns: columns.as_slice(),
+        });
+        let (plan, page_ids) = plan_on_new_pages(
+            doc,
+            page_id,
+            start_region,
+            &band,
+            &band_layout,
+            band_page_break_rows
+                .as_ref()
+                .map(|rows| rows as &dyn PageBreakRows),
+            position,
+            first_page_index,
+        )?;
+        let result = draw_planned_pages(
+            doc,
+            &page_ids,
+            &band,
+            &plan,
+            band_hook.as_mut().map(|h| h as &mut dyn TaggedCellHook),
+            image_registry,
+        )?;
+
+        split_rows.extend(result.split_rows);
+        rows.extend(result.rows);
+        cells.extend(result.cells.into_iter().map(|cell| PlacedCell {
+            column: columns[cell.column],
+            ..cell
+        }));
+        final_position = result.final_position;
+        band_pages.push(result.page_ids);
+        band_bounds.push(
+            result
+                .table_bounds
+                .into_iter()
+                .zip(plan.pages.iter().map(|page| page.region))
+                .collect(),
+        );
+    }
+    split_rows.sort_unstable();
+    split_rows.dedup();
+
+    let (page_ids, page_bounds): (Vec<ObjectId>, Vec<(Rect, PageBox)>) = match config.order {
+        BandOrder::DownThenAcross => band_pages
+            .into_iter()
+            .flatten()
+            .zip(band_bounds.into_iter().flatten())
+            .collect(),
+        BandOrder::AcrossThenDown => {
+            let depth = band_pages.iter().map(Vec::len).max().unwrap_or(0);
+            let ordered: (Vec<ObjectId>, Vec<(Rect, PageBox)>) = (0..depth)
+                .flat_map(|v| {
+                    band_pages
+                        .iter()
+                        .zip(&band_bounds)
+                        .filter_map(move |(pages, bounds)| Some((*pages.get(v)?, bounds[v])))
+                })
+                .collect();
+            reorder_pages(doc, &ordered.0)?;
+            ordered
+        }
+    };
+
+    // Placements follow the final page order
+    let page_order = |page_id: ObjectId| page_ids.iter().position(|&id| id == page_id);
+    rows.sort_by_key(|row| page_order(row.page_id));
+    cells.sort_by_key(|cell| page_order(cell.page_id));
+
+    let (table_bounds, regions): (Vec<Rect>, Vec<PageBox>) = page_bounds.into_iter().unzip();
+    let result = PagedTableResult {
+        total_pages: page_ids.len(),
+        page_ids,
+        final_position,
+        split_rows,
+        table_bounds,
+        rows,
+        cells,
+    };
+    decorate_pages(doc, table, &result, &regions, hook.is_some())?;
+    Ok(result)
+}
+
+/// Rearrange pages so they appear in the given order, occupying the same
+/// slots of the page tree. A page moved into a slot under another Pages node
+/// takes that node as its parent.
+fn reorder_pages(doc: &mut Document, ordered: &[ObjectId]) -> Result<()> {
+    let page_numbers: HashMap<ObjectId, u32> = doc
+        .get_pages()
+        .into_iter()
+        .map(|(number, page_id)| (page_id, number))
+        .collect();
+
+    // Slots of the pages in the page tree, in page order
+    let mut slots = ordered
+        .iter()
+        .map(|&page_id| {
+            let missing = || {
+                TableError::DrawingError(format!(
+                    "Could not find page {page_id:?} in the page tree"
+                ))
+            };
+            let parent_id = doc
+                .get_dictionary(page_id)?
+                .get(b"Parent")
+                .and_then(Object::as_reference)
+                .map_err(|_| missing())?;
+            let slot = doc
+                .get_dictionary(parent_id)?
+                .get(b"Kids")
+                .and_then(Object::as_array)?
+                .iter()
+                .position(|kid| kid.as_reference().is_ok_and(|id| id == page_id))
+                .ok_or_else(missing)?;
+            let number = page_numbers.get(&page_id).copied().ok_or_else(missing)?;
+            Ok((number, parent_id, slot))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    slots.sort_unstable_by_key(|&(number, ..)| number);
+
+    for ((_, parent_id, slot), &page_id) in slots.into_iter().zip(ordered) {
+        doc.get_dictionary_mut(parent_id)?
+            .get_mut(b"Kids")
+            .and_then(Object::as_array_mut)?[slot] = Object::Reference(page_id);
+        doc.get_dictionary_mut(page_id)?.set("Parent", parent_id);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TableDrawing;
+    use crate::layout::calculate_layout;
+    use crate::page_break::PageBreak;
+    use crate::style::{BandOrder, TableStyle};
+    use crate::table::Cell;
+    use crate::test_support::*;
+
+    #[test]
+    fn test_column_bands_repeat_key_columns() {
+        let table = Table::new()
+            .with_pixel_widths(vec![50.0, 100.0, 100.0, 100.0, 100.0])
+            .add_row(Row::new(vec![
+                Cell::new("ID"),
+                Cell::new("A"),
+                Cell::new("B"),
+                Cell::new("C"),
+                Cell::new("D"),
+            ]));
+        let layout = calculate_layout(&table).unwrap();
+        let config = HorizontalPagination::new().with_key_columns(vec![0]);
+
+        assert_eq!(
+            column_bands(&layout, &config, 260.0),
+            vec![vec![0, 1, 2], vec![0, 3, 4]]
+        );
+    }
+
+    #[test]
+    fn test_band_layout_slices_full_layout() {
+        let table = Table::new()
+            .with_pixel_widths(vec![50.0, 80.0, 80.0, 80.0])
+            .add_row(Row::new(vec![
+                Cell::new("ID"),
+                Cell::new("Spanning the last three columns").with_colspan(3),
+            ]))
+            .add_row(Row::new(vec![
+                Cell::new("1"),
+                Cell::new("A fairly long first cell").with_wrap(true),
+                Cell::new("B"),
+                Cell::new("C"),
+            ]));
+        let layout = calculate_layout(&table).unwrap();
+        let columns = [0, 1, 2];
+        let band = band_table(&table, &layout, &columns);
+
+        let sliced = band_layout(&band, &layout, &columns).unwrap();
+
+        assert_eq!(sliced.column_widths, vec![50.0, 80.0, 80.0]);
+        assert!(approx_eq(sliced.total_width, 210.0));
+        assert_eq!(sliced.row_heights, layout.row_heights);
+        // Cells within the band keep their wrapped lines
+        assert_eq!(sliced.text_lines(1, 1), layout.text_lines(1, 1));
+        // The clipped span is wrapped at the two columns left in the band
+        let laid_out = calculate_layout(&band).unwrap();
+        assert_eq!(sliced.text_lines(0, 1), laid_out.text_lines(0, 1));
+    }
+
+    #[test]
+    fn test_band_width_defaults_to_page_margins() {
+        let page = PageBox::new(612.0, 792.0);
+        let mut table = Table::new();
+        assert_eq!(band_width(&table, &page), None);
+
+        table.style.horizontal_pagination = Some(HorizontalPagination::new());
+        table.style.left_margin = 36.0;
+        table.style.right_margin = 36.0;
+        assert_eq!(band_width(&table, &page), Some(540.0));
+
+        table.style.horizontal_pagination = Some(HorizontalPagination::new().with_max_width(300.0));
+        assert_eq!(band_width(&table, &page), Some(300.0));
+    }
+
+    #[test]
+    fn test_reorder_pages_across_pages_nodes() {
+        use lopdf::dictionary;
+
+        let mut doc = Document::with_version("1.7");
+        let root_id = doc.new_object_id();
+        let branch = |doc: &mut Document| {
+            let branch_id = doc.new_object_id();
+            let pages: Vec<ObjectId> = (0..2)
+                .map(|_| doc.add_object(dictionary! { "Type" => "Page", "Parent" => branch_id }))
+                .collect();
+            doc.objects.insert(
+                branch_id,
+                dictionary! {
+                    "Type" => "Pages",
+                    "Parent" => root_id,
+                    "Kids" => pages.iter().map(|&id| id.into()).collect::<Vec<Object>>(),
+                    "Count" => 2,
+                }
+                .into(),
+            );
+            (branch_id, pages)
+        };
+        let (first_branch, first_pages) = branch(&mut doc);
+        let (second_branch, second_pages) = branch(&mut doc);
+        doc.objects.insert(
+            root_id,
+            dictionary! {
+                "Type" => "Pages",
+                "Kids" => vec![first_branch.into(), second_branch.into()],
+                "Count" => 4,
+            }
+            .into(),
+        );
+        let catalog_id = doc.add_object(dictionary! { "Type" => "Catalog", "Pages" => root_id });
+        doc.trailer.set("Root", catalog_id);
+
+        let ordered = [
+            first_pages[0],
+            second_pages[0],
+            first_pages[1],
+            second_pages[1],
+        ];
+        reorder_pages(&mut doc, &ordered).unwrap();
+
+        let pages: Vec<ObjectId> = doc.get_pages().into_values().collect();
+        assert_eq!(pages, ordered);
+        let parent = |page_id: ObjectId| {
+            doc.get_dictionary(page_id)
+                .unwrap()
+                .get(b"Parent")
+                .and_then(Object::as_reference)
+                .unwrap()
+        };
+        assert_eq!(parent(second_pages[0]), first_branch);
+        assert_eq!(parent(first_pages[1]), second_branch);
+    }
+
+    #[test]
+    fn test_project_row_clips_colspan_to_band() {
+        let table = Table::new()
+            .add_row(Row::new(vec![
+                Cell::new("ID"),
+                Cell::new("Group").with_colspan(3),
+            ]))
+            .add_row(Row::new(vec![
+                Cell::new("1"),
+                Cell::new("a"),
+                Cell::new("b"),
+                Cell::new("c"),
+            ]));
+        let grid = CellGrid::new(&table).unwrap();
+
+        let row = project_row(&table.rows[0], grid.placements_in_row(0), &[0, 3]);
+        assert_eq!(row.cells.len(), 2);
+        assert_eq!(row.cells[1].content, "Group");
+        assert_eq!(row.cells[1].colspan, 1);
+    }
+
+    fn wide_table(order: BandOrder) -> Table {
+        let style = TableStyle {
+            page_height: Some(842.0),
+            horizontal_pagination: Some(
+                HorizontalPagination::new()
+                    .with_key_columns(vec![0])
+                    .with_order(order),
+            ),
+            ..Default::default()
+        };
+        let mut table = Table::new()
+            .with_style(style)
+            .with_header_rows(1)
+            .with_pixel_widths(vec![60.0, 200.0, 200.0, 200.0])
+            .add_row(Row::new(vec![
+                Cell::new("ID"),
+                Cell::new("First"),
+                Cell::new("Second"),
+                Cell::new("Third"),
+            ]));
+        for row in 0..40 {
+            table = table.add_row(
+                Row::new(vec![
+                    Cell::new(format!("id-{row}")),
+                    Cell::new("a"),
+                    Cell::new("b"),
+                    Cell::new("c"),
+                ])
+                .with_height(30.0),
+            );
+        }
+        table
+    }
+
+    #[test]
+    fn test_horizontal_pagination_down_then_across() {
+        let (mut doc, page_id) = make_test_doc();
+        let result = doc
+            .draw_table_with_pagination(
+                page_id,
+                wide_table(BandOrder::DownThenAcross),
+                (50.0, 792.0),
+            )
+            .expect("banded table draw should succeed");
+
+        // Two vertical pages per band, bands one after the other
+        assert_eq!(result.total_pages, 4);
+        assert_eq!(page_tree_order(&doc), result.page_ids);
+        for (idx, &page) in result.page_ids.iter().enumerate() {
+            let texts = page_texts(&doc, page);
+            assert_eq!(texts[0], "ID", "key column repeated on page {idx}");
+            let first_band = idx < 2;
+            assert_eq!(texts.contains(&"First".to_string()), first_band);
+            assert_eq!(texts.contains(&"Third".to_string()), !first_band);
+            let extents = page_rect_extents(&doc, page).expect("page should have rects");
+            assert!(extents.max_right <= 50.0 + 495.0 + 0.01);
+        }
+    }
+
+    /// Brings forward the page index and the last row placed on the full table
+    struct PageIndexRows;
+
+    impl PageBreakRows for PageIndexRows {
+        fn carried_forward(&self, _page_break: &PageBreak<'_>) -> Option<Row> {
+            None
+        }
+
+        fn brought_forward(&self, page_break: &PageBreak<'_>) -> Option<Row> {
+            let last = page_break.page_rows().last()?;
+            let cells = (0..page_break.table.rows[*last].cells.len())
+                .map(|col| Cell::new(format!("bf-{}-{col}", page_break.page_index)))
+                .collect();
+            Some(Row::new(cells))
+        }
+    }
+
+    #[test]
+    fn test_horizontal_pagination_page_break_rows_span_full_table() {
+        let (mut doc, page_id) = make_test_doc();
+        let mut table = wide_table(BandOrder::DownThenAcross);
+        table.page_break_rows = Some(std::sync::Arc::new(PageIndexRows));
+
+        let result = doc
+            .draw_table_with_pagination(page_id, table, (50.0, 792.0))
+            .expect("banded table draw should succeed");
+
+        // Rows are generated over all columns and projected onto each band,
+        // with the page's index among all of the table's pages
+        assert_eq!(result.total_pages, 4);
+        assert!(page_texts(&doc, result.page_ids[1]).contains(&"bf-0-2".to_string()));
+        let last_band = page_texts(&doc, result.page_ids[3]);
+        assert!(last_band.contains(&"bf-2-0".to_string()));
+        assert!(last_band.contains(&"bf-2-3".to_string()));
+        assert!(!last_band.contains(&"bf-2-1".to_string()));
+    }
+
+    #[test]
+    fn test_horizontal_pagination_across_then_down_reorders_pages() {
+        let (mut doc, page_id) = make_test_doc();
+        let result = doc
+            .draw_table_with_pagination(
+                page_id,
+                wide_table(BandOrder::AcrossThenDown),
+                (50.0, 792.0),
+            )
+            .expect("banded table draw should succeed");
+
+        assert_eq!(result.total_pages, 4);
+        assert_eq!(result.page_ids[0], page_id);
+        assert_eq!(page_tree_order(&doc), result.page_ids);
+        let bands: Vec<bool> = result
+            .page_ids
+            .iter()
+            .map(|&page| page_texts(&doc, page).contains(&"First".to_string()))
+            .collect();
+        assert_eq!(bands, vec![true, false, true, false]);
+
+        // The second vertical page of each band continues after the same row
+        let second_page_ids: Vec<Option<String>> = [result.page_ids[2], result.page_ids[3]]
+            .iter()
+            .map(|&page| {
+                page_texts(&doc, page)
+                    .into_iter()
+                    .find(|t| t.starts_with("id-"))
+            })
+            .collect();
+        assert!(second_page_ids[0].is_some());
+        assert_eq!(second_page_ids[0], second_page_ids[1]);
+    }
+
+    #[test]
+    fn test_page_factory_indexes_count_across_bands() {
+        let (mut doc, page_id) = make_test_doc();
+        let factory = std::sync::Arc::new(LetterheadPages {
+            indexes: std::sync::Mutex::new(Vec::new()),
+        });
+        let mut table = wide_table(BandOrder::DownThenAcross);
+        table.page_factory = Some(factory.clone());
+
+        let result = doc
+            .draw_table_with_pagination(page_id, table, (50.0, 792.0))
+            .unwrap();
+
+        // Every page after the first comes from the factory, with its index
+        // among all of the table's pages
+        let indexes = factory.indexes.lock().unwrap().clone();
+        assert_eq!(indexes, (1..result.total_pages).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_band_starting_on_factory_page_uses_its_region() {
+        let (mut doc, page_id) = make_test_doc();
+        let mut table = wide_table(BandOrder::DownThenAcross);
+        table.page_factory = Some(std::sync::Arc::new(LetterheadPages {
+            indexes: std::sync::Mutex::new(Vec::new()),
+        }));
+
+        let result = doc
+            .draw_table_with_pagination(page_id, table, (50.0, 792.0))
+            .unwrap();
+
+        // The last band starts below the letterhead of its factory page
+        let band_start = *result
+            .page_ids
+            .iter()
+            .find(|&&page| page_texts(&doc, page).contains(&"Third".to_string()))
+            .unwrap();
+        let rects: Vec<Vec<f32>> = page_content_operations(&doc, band_start)
+            .iter()
+            .filter(|op| op.operator == "re")
+            .map(|op| op.operands.iter().filter_map(object_to_f32).collect())
+            .collect();
+        let rows_top = rects[1..]
+            .iter()
+            .map(|r| r[1] + r[3])
+            .fold(f32::MIN, f32::max);
+        let rows_bottom = rects[1..].iter().map(|r| r[1]).fold(f32::MAX, f32::min);
+        assert!(approx_eq(rows_top, 600.0));
+        assert!(rows_bottom >= 80.0 - 0.01);
+    }
+}
@@ -1,23 +1,33 @@
 //! PDF drawing operations for tables
 
-use crate::PagedTableResult;
 use crate::Result;
 use crate::TaggedCellHook;
 use crate::constants::*;
 use crate::drawing_utils::{
-    BorderDrawingMode, calculate_cell_width, draw_horizontal_line, draw_rectangle_fill,
-    draw_rectangle_stroke, draw_table_borders as draw_borders_util, draw_vertical_line,
-    objects_to_operations, set_stroke_style,
+    calculate_cell_width, draw_horizontal_line, draw_rectangle_fill, draw_rectangle_stroke,
+    draw_table_borders as draw_borders_util, draw_vertical_line, objects_to_operations,
+    set_stroke_style,
 };
-use crate::layout::TableLayout;
+use crate::font::FontMetrics;
+use crate::grid::CellPlacement;
+use crate::layout::{TableLayout, TextLine};
+use crate::page::{
+    DecoratedPage, PageBox, Rect, ResourceReferences, inherited_attribute, insert_page_after,
+};
+use crate::page_break::{PageBreak, PageBreakRows};
+use crate::plan::{PagePlan, PlannedRow, TablePlan};
+use crate::standard_fonts::StandardFont;
 use crate::style::{Alignment, BorderStyle, Color, VerticalAlignment};
-use crate::table::{CellImage, Table};
+use crate::table::{CellImage, ColumnWidth, PageFill, Row, Table};
+use crate::{PagedTableResult, PlacedCell, PlacedRow};
 use lopdf::{
     Document, Object, ObjectId, StringFormat,
     content::{Content, Operation},
     dictionary,
 };
-use std::collections::HashMap;
+use std::borrow::Cow;
+use std::collections::{BTreeSet, HashMap};
+use std::ops::Range;
 use std::sync::Arc;
 use tracing::{debug, trace};
 
@@ -70,56 +80,38 @@ impl ImageXObjects {
     }
 
     /// Register the ExtGState into a page's Resources/ExtGState dictionary.
-    fn register_gstate_on_page(&self, doc: &mut Document, page_id: ObjectId) -> Result<()> {
+    fn register_gstate_on_page(
+        &self,
+        doc: &mut Document,
+        references: &mut ResourceReferences,
+        page_id: ObjectId,
+    ) -> Result<()> {
         let gstate_obj_id = match self.gstate_id {
             Some(id) => id,
             None => return Ok(()),
         };
 
-        // Get the Resources dictionary reference from the page
-        let resources_ref = if let Ok(Object::Dictionary(page_dict)) = doc.get_object(page_id) {
-            match page_dict.get(b"Resources") {
-                Ok(Object::Reference(r)) => Some(*r),
-                _ => None,
-            }
-        } else {
-            None
-        };
-
-        let resources_id = match resources_ref {
-            Some(id) => id,
-            None => return Ok(()),
-        };
-
-        // Get or create ExtGState sub-dictionary on the Resources dictionary
-        if let Ok(Object::Dictionary(res_dict)) = doc.get_object_mut(resources_id) {
-            match res_dict.get_mut(b"ExtGState") {
-                Ok(Object::Dictionary(gs_dict)) => {
-                    gs_dict.set(OVERLAY_GSTATE_NAME, gstate_obj_id);
-                }
-                _ => {
-                    let gs_sub = dictionary! {
-                        OVERLAY_GSTATE_NAME => gstate_obj_id,
-                    };
-                    res_dict.set("ExtGState", gs_sub);
-                }
-            }
-        }
-
-        Ok(())
+        crate::page::add_page_resource(
+            doc,
+            references,
+            page_id,
+            "ExtGState",
+            OVERLAY_GSTATE_NAME,
+            gstate_obj_id,
+        )
     }
 
     /// Register all image XObjects (and ExtGState if needed) into a page's Resources.
-    pub(crate) fn register_on_page(&self, doc: &mut Document, page_id: ObjectId) -> Result<()> {
+    pub(crate) fn register_on_page(
+        &self,
+        doc: &mut Document,
+        references: &mut ResourceReferences,
+        page_id: ObjectId,
+    ) -> Result<()> {
         for (_, (name, obj_id)) in &self.entries {
-            doc.add_xobject(page_id, name.as_bytes().to_vec(), *obj_id)
-                .map_err(|e| {
-                    crate::error::TableError::DrawingError(format!(
-                        "Failed to register image XObject: {e}"
-                    ))
-                })?;
+            crate::page::add_page_resource(doc, references, page_id, "XObject", name, *obj_id)?;
         }
-        self.register_gstate_on_page(doc, page_id)?;
+        self.register_gstate_on_page(doc, references, page_id)?;
         Ok(())
     }
 }
@@ -179,7 +171,7 @@ fn resolve_overlay_font_resource_name(table: &Table) -> String {
     if let Some(ref name) = table.style.embedded_font_resource_name {
         name.clone()
     } else {
-        "F1".to_string()
+        StandardFont::Helvetica.resource_name().to_string()
     }
 }
 
@@ -355,7 +347,10 @@ fn draw_image_overlay(
         objects.push(Object::String(encoded, StringFormat::Hexadecimal));
     } else {
         objects.push(Object::Name(b"Tj".to_vec()));
-        objects.push(Object::string_literal(overlay.text.clone()));
+        objects.push(Object::String(
+            StandardFont::Helvetica.encode_text(&overlay.text),
+            StringFormat::Literal,
+        ));
     }
 
     objects.push(Object::Name(b"ET".to_vec()));
@@ -482,95 +477,196 @@ pub fn generate_table_operations(
     position: (f32, f32),
     mut hook: Option<&mut dyn TaggedCellHook>,
     image_registry: Option<&ImageXObjects>,
+) -> Result<Vec<Object>> {
+    debug!(
+        "Generating operations for table at ({}, {})",
+        position.0, position.1
+    );
+
+    let rows: Vec<PageRow> = (0..table.rows.len())
+        .map(|row| PageRow::whole(row, layout))
+        .collect();
+    let operations =
+        generate_rows_operations(table, layout, &rows, position, &mut hook, image_registry)?;
+
+    trace!("Generated {} operations", operations.len());
+    Ok(operations)
+}
+
+/// A row, or a slice of a row split across pages, as placed on one page
+#[derive(Debug, Clone)]
+pub(crate) struct PageRow {
+    /// Index of the row in `Table::rows`
+    pub row: usize,
+    /// Height the row occupies on this page
+    pub height: f32,
+    /// Range of text lines shown per cell (indexed like `Row::cells`) when the row is split
+    pub lines: Option<Vec<Range<usize>>>,
+    /// Whether this slice continues a row started on an earlier page
+    pub continued: bool,
+}
+
+impl PageRow {
+    /// The full row at its laid-out height
+    pub fn whole(row: usize, layout: &TableLayout) -> Self {
+        Self {
+            row,
+            height: layout.row_heights[row],
+            lines: None,
+            continued: false,
+        }
+    }
+
+    /// Line range to draw for a cell of this row, if the row is split
+    fn cell_lines(&self, placement: &CellPlacement) -> Option<Range<usize>> {
+        self.lines
+            .as_ref()
+            .filter(|_| placement.row == self.row)
+            .map(|lines| lines[placement.cell].clone())
+    }
+}
+
+/// A cell to draw for one row of a page, with its height merged across the
+/// spanned rows that are drawn on the same page.
+struct VisibleCell {
+    placement: CellPlacement,
+    height: f32,
+}
+
+/// Cells whose drawing starts at position `entry` of a page's row list.
+///
+/// A cell spanning several rows is drawn once, at the first of its rows present
+/// on the page, with the combined height of its consecutive rows on that page.
+fn visible_cells(layout: &TableLayout, rows: &[PageRow], entry: usize) -> Vec<VisibleCell> {
+    let row_idx = rows[entry].row;
+    let continues_from_previous_entry = |placement: &CellPlacement| -> bool {
+        entry > 0 && rows[entry - 1].row + 1 == row_idx && placement.covers_row(rows[entry - 1].row)
+    };
+
+    layout
+        .grid
+        .placements_covering_row(row_idx)
+        .filter(|placement| !continues_from_previous_entry(placement))
+        .map(|placement| {
+            let mut height = rows[entry].height;
+            let mut next = entry + 1;
+            while next < rows.len()
+                && rows[next].row == rows[next - 1].row + 1
+                && placement.covers_row(rows[next].row)
+            {
+                height += rows[next].height;
+                next += 1;
+            }
+            VisibleCell {
+                placement: *placement,
+                height,
+            }
+        })
+        .collect()
+}
+
+/// Generate the operations for an ordered list of rows stacked from `position`.
+///
+/// Used both for whole-table drawing and for the rows placed on one page of a
+/// paginated table (where header rows may precede a non-contiguous body range).
+fn generate_rows_operations(
+    table: &Table,
+    layout: &TableLayout,
+    rows: &[PageRow],
+    position: (f32, f32),
+    hook: &mut Option<&mut dyn TaggedCellHook>,
+    image_registry: Option<&ImageXObjects>,
 ) -> Result<Vec<Object>> {
     let mut operations = Vec::new();
     let mut cell_border_overlay_ops = Vec::new();
     let (start_x, start_y) = position;
     let artifactize_non_semantic = hook.is_some();
 
-    debug!(
-        "Generating operations for table at ({}, {})",
-        start_x, start_y
-    );
+    let subset_height: f32 = rows.iter().map(|r| r.height).sum();
 
-    // Draw table background if specified
-    if let Some(bg_color) = &table.style.background_color {
-        let bg_ops = draw_rectangle_fill(
-            start_x,
-            start_y - layout.total_height,
-            layout.total_width,
-            layout.total_height,
-            *bg_color,
-        );
-        if artifactize_non_semantic {
-            operations.extend(wrap_objects_as_artifact(bg_ops));
-        } else {
-            operations.extend(bg_ops);
+    // Draw table background if the table starts in this row range
+    if rows.iter().any(|r| r.row == 0) {
+        if let Some(bg_color) = &table.style.background_color {
+            let bg_ops = draw_rectangle_fill(
+                start_x,
+                start_y - subset_height,
+                layout.total_width,
+                subset_height,
+                *bg_color,
+            );
+            if artifactize_non_semantic {
+                operations.extend(wrap_objects_as_artifact(bg_ops));
+            } else {
+                operations.extend(bg_ops);
+            }
         }
     }
 
-    // Draw cells and content
     let mut current_y = start_y;
 
-    for (row_idx, row) in table.rows.iter().enumerate() {
-        let row_height = layout.row_heights[row_idx];
-        let mut current_x = start_x;
-
-        // Draw row background if specified
-        if let Some(ref row_style) = row.style {
-            if let Some(bg_color) = row_style.background_color {
-                let row_bg_ops = draw_rectangle_fill(
-                    start_x,
-                    current_y - row_height,
-                    layout.total_width,
-                    row_height,
-                    bg_color,
-                );
-                if artifactize_non_semantic {
-                    operations.extend(wrap_objects_as_artifact(row_bg_ops));
-                } else {
-                    operations.extend(row_bg_ops);
-                }
+    for (entry, page_row) in rows.iter().enumerate() {
+        let row = &table.rows[page_row.row];
+        let cells = visible_cells(layout, rows, entry);
+
+        // Draw row background if specified, merged down across rowspans
+        if let Some(bg_color) = row.style.as_ref().and_then(|s| s.background_color) {
+            let row_bg_ops = draw_row_background(layout, &cells, start_x, current_y, bg_color);
+            if artifactize_non_semantic {
+                operations.extend(wrap_objects_as_artifact(row_bg_ops));
+            } else {
+                operations.extend(row_bg_ops);
             }
         }
 
-        let mut col_idx = 0;
-        for cell in row.cells.iter() {
-            if col_idx >= layout.column_widths.len() {
-                break;
-            }
-            let is_header = row_idx < table.header_rows;
+        for VisibleCell { placement, height } in cells {
+            let cell = &table.rows[placement.row].cells[placement.cell];
+            let col_idx = placement.col;
+            let is_header = placement.row < table.header_rows;
+            let cell_x = start_x + layout.column_widths[..col_idx].iter().sum::<f32>();
 
             if let Some(cell_hook) = hook.as_deref_mut() {
-                operations.extend(operations_to_objects(
-                    cell_hook.begin_cell(row_idx, col_idx, is_header),
-                ));
+                operations.extend(operations_to_objects(cell_hook.begin_cell(
+                    placement.row,
+                    col_idx,
+                    is_header,
+                )));
             }
 
             // Calculate the total width for cells with colspan
-            let cell_width = calculate_cell_width(col_idx, cell.colspan, &layout.column_widths);
+            let cell_width =
+                calculate_cell_width(col_idx, placement.colspan, &layout.column_widths);
 
             // Draw cell background if specified
             if let Some(ref cell_style) = cell.style {
                 if let Some(bg_color) = cell_style.background_color {
                     operations.extend(draw_rectangle_fill(
-                        current_x,
-                        current_y - row_height,
+                        cell_x,
+                        current_y - height,
                         cell_width,
-                        row_height,
+                        height,
                         bg_color,
                     ));
                 }
             }
 
             // Draw cell content (text)
+            let bounds = Rect {
+                x: cell_x,
+                y: current_y - height,
+                width: cell_width,
+                height,
+            };
             operations.extend(draw_cell_text(
-                cell, table, current_x, current_y, cell_width, row_height,
+                cell,
+                table,
+                bounds,
+                layout.text_lines(placement.row, placement.cell),
+                page_row.cell_lines(&placement),
             )?);
 
-            // Draw cell images if present
+            // Draw cell images if present (only in the first slice of a split row)
             if let Some(registry) = image_registry {
-                if !cell.images.is_empty() {
+                if !cell.images.is_empty() && !page_row.continued {
                     let padding = cell
                         .style
                         .as_ref()
@@ -579,10 +675,10 @@ pub fn generate_table_operations(
                     operations.extend(draw_cell_images(
                         &cell.images,
                         registry,
-                        current_x,
+                        cell_x,
                         current_y,
                         cell_width,
-                        row_height,
+                        height,
                         padding,
                         table,
                     ));
@@ -590,32 +686,30 @@ pub fn generate_table_operations(
             }
 
             if let Some(cell_hook) = hook.as_deref_mut() {
-                operations.extend(operations_to_objects(
-                    cell_hook.end_cell(row_idx, col_idx, is_header),
-                ));
+                operations.extend(operations_to_objects(cell_hook.end_cell(
+                    placement.row,
+                    col_idx,
+                    is_header,
+                )));
             }
 
             // Draw per-cell border overrides after semantic cell content so they remain visual-only.
             if let Some(ref cell_style) = cell.style {
-                let cell_border_ops = draw_cell_border_overrides(
-                    cell_style, current_x, current_y, cell_width, row_height,
-                );
+                let cell_border_ops =
+                    draw_cell_border_overrides(cell_style, cell_x, current_y, cell_width, height);
                 if artifactize_non_semantic {
                     cell_border_overlay_ops.extend(wrap_objects_as_artifact(cell_border_ops));
                 } else {
                     cell_border_overlay_ops.extend(cell_border_ops);
                 }
             }
-
-            current_x += cell_width;
-            col_idx += cell.colspan.max(1);
         }
 
-        current_y -= row_height;
+        current_y -= page_row.height;
     }
 
-    // Draw table borders
-    let border_ops = draw_table_borders(table, layout, position);
+    // Draw borders for these rows
+    let border_ops = draw_borders_util(table, layout, position, rows);
     if artifactize_non_semantic {
         operations.extend(wrap_objects_as_artifact(border_ops));
     } else {
@@ -623,59 +717,160 @@ pub fn generate_table_operations(
     }
     operations.extend(cell_border_overlay_ops);
 
-    trace!("Generated {} operations", operations.len());
     Ok(operations)
 }
 
-/// Draw table borders (wrapper for the shared utility)
-fn draw_table_borders(table: &Table, layout: &TableLayout, position: (f32, f32)) -> Vec<Object> {
-    draw_borders_util(table, layout, position, BorderDrawingMode::Full, None)
+/// Fill a row background behind the cells starting in that row.
+///
+/// Adjacent cells of equal height are merged so a row without rowspans is
+/// filled with a single rectangle.
+fn draw_row_background(
+    layout: &TableLayout,
+    cells: &[VisibleCell],
+    start_x: f32,
+    top_y: f32,
+    color: Color,
+) -> Vec<Object> {
+    let mut ops = Vec::new();
+    let mut run: Option<(f32, f32, f32)> = None; // (x, width, height)
+
+    for visible in cells {
+        let x = start_x
+            + layout.column_widths[..visible.placement.col]
+                .iter()
+                .sum::<f32>();
+        let width = calculate_cell_width(
+            visible.placement.col,
+            visible.placement.colspan,
+            &layout.column_widths,
+        );
+        run = match run {
+            Some((run_x, run_w, run_h))
+                if (run_h - visible.height).abs() < f32::EPSILON
+                    && (run_x + run_w - x).abs() < 0.001 =>
+            {
+                Some((run_x, run_w + width, run_h))
+            }
+            Some((run_x, run_w, run_h)) => {
+                ops.extend(draw_rectangle_fill(
+                    run_x,
+                    top_y - run_h,
+                    run_w,
+                    run_h,
+                    color,
+                ));
+                Some((x, width, visible.height))
+            }
+            None => Some((x, width, visible.height)),
+        };
+    }
+    if let Some((run_x, run_w, run_h)) = run {
+        ops.extend(draw_rectangle_fill(
+            run_x,
+            top_y - run_h,
+            run_w,
+            run_h,
+            color,
+        ));
+    }
+
+    ops
 }
 
 fn cell_is_bold(cell: &crate::table::Cell) -> bool {
     cell.style.as_ref().map(|s| s.bold).unwrap_or(false)
 }
 
+fn cell_is_italic(cell: &crate::table::Cell) -> bool {
+    cell.style.as_ref().map(|s| s.italic).unwrap_or(false)
+}
+
 fn metrics_for_cell<'a>(
     table: &'a Table,
     cell: &crate::table::Cell,
 ) -> Option<&'a dyn crate::font::FontMetrics> {
-    if cell_is_bold(cell) {
-        table
-            .bold_font_metrics
-            .as_ref()
-            .map(|m| m.as_ref())
-            .or(table.font_metrics.as_ref().map(|m| m.as_ref()))
-    } else {
-        table.font_metrics.as_ref().map(|m| m.as_ref())
-    }
+    table.font_metrics_for(cell_is_bold(cell), cell_is_italic(cell))
 }
 
-/// Estimate text width, using selected font metrics when available
-fn measure_text_width(
-    text: &str,
-    font_size: f32,
-    metrics: Option<&dyn crate::font::FontMetrics>,
-) -> f32 {
-    if let Some(metrics) = metrics {
-        metrics.text_width(text, font_size)
+/// Resource name of the embedded font a cell's text is drawn with:
+/// 1. Cell's embedded_font_resource_name (if set)
+/// 2. Table style's embedded font for the cell's bold and italic (if set),
+///    falling back like the font metrics
+fn embedded_font_name<'a>(table: &'a Table, cell: &'a crate::table::Cell) -> Option<&'a str> {
+    cell.style
+        .as_ref()
+        .and_then(|s| s.embedded_font_resource_name.as_deref())
+        .or_else(|| {
+            table
+                .style
+                .embedded_font_resource_name_for(cell_is_bold(cell), cell_is_italic(cell))
+        })
+}
+
+/// Standard fonts a table's text may be drawn with: those of its cells and
+/// of the filler row template without an embedded font, the table's font and
+/// its bold variant (every variant when rows are generated at page breaks),
+/// and those of image overlays (Helvetica) and page decorations
+pub(crate) fn standard_fonts_used(table: &Table) -> BTreeSet<StandardFont> {
+    let filler_cells = match &table.page_fill {
+        Some(PageFill::FillerRows(template)) => template.cells.as_slice(),
+        _ => &[],
+    };
+    let mut fonts: BTreeSet<StandardFont> = table
+        .rows
+        .iter()
+        .flat_map(|row| &row.cells)
+        .chain(filler_cells)
+        .filter(|cell| embedded_font_name(table, cell).is_none())
+        .map(|cell| StandardFont::for_cell(table, cell))
+        .collect();
+
+    let variants: &[(bool, bool)] = if table.page_break_rows.is_some() {
+        &[(false, false), (true, false), (false, true), (true, true)]
     } else {
-        text.chars().count() as f32 * font_size * DEFAULT_CHAR_WIDTH_RATIO
+        &[(false, false), (true, false)]
+    };
+    for &(bold, italic) in variants {
+        if table
+            .style
+            .embedded_font_resource_name_for(bold, italic)
+            .is_none()
+        {
+            fonts.insert(StandardFont::for_font_name(
+                &table.style.font_name,
+                bold,
+                italic,
+            ));
+        }
+    }
+
+    let overlay_in_helvetica =
+        table.style.embedded_font_resource_name.is_none() && table_has_images(table);
+    if overlay_in_helvetica {
+        fonts.insert(StandardFont::Helvetica);
+    }
+    if let Some(decorator) = &table.page_decorator {
+        fonts.extend(decorator.standard_fonts());
     }
+    fonts
 }
 
-/// Draw text within a cell (returns Operation objects directly)
+/// Draw the lines of text of a cell wrapped by the layout (returns Operation
+/// objects directly).
+///
+/// When `line_range` is set only those lines are drawn, top-aligned, as one
+/// slice of a row split across pages.
 fn draw_cell_text_operations(
     cell: &crate::table::Cell,
     table: &Table,
-    x: f32,
-    y: f32,
-    width: f32,
-    height: f32,
+    bounds: Rect,
+    lines: &[TextLine],
+    line_range: Option<Range<usize>>,
 ) -> Vec<Operation> {
-    if cell.content.is_empty() {
-        return Vec::new();
-    }
+    let Rect {
+        x, width, height, ..
+    } = bounds;
+    let y = bounds.top();
 
     let mut operations = Vec::new();
 
@@ -698,11 +893,14 @@ fn draw_cell_text_operations(
         .map(|s| s.alignment)
         .unwrap_or(Alignment::Left);
 
-    let v_alignment = cell
-        .style
-        .as_ref()
-        .map(|s| s.vertical_alignment)
-        .unwrap_or(VerticalAlignment::Middle);
+    let v_alignment = if line_range.is_some() {
+        VerticalAlignment::Top
+    } else {
+        cell.style
+            .as_ref()
+            .map(|s| s.vertical_alignment)
+            .unwrap_or(VerticalAlignment::Middle)
+    };
 
     // Calculate text position with padding
     let padding = cell
@@ -711,22 +909,13 @@ fn draw_cell_text_operations(
         .and_then(|s| s.padding.as_ref())
         .unwrap_or(&table.style.padding);
 
-    // Calculate available width for text
-    let available_width = width - padding.left - padding.right;
-    let metrics = metrics_for_cell(table, cell);
-    let is_bold = cell_is_bold(cell);
-
-    // Wrap text if enabled
-    let lines = if cell.text_wrap {
-        if let Some(metrics) = metrics {
-            crate::text::wrap_text_with_metrics(&cell.content, available_width, font_size, metrics)
-        } else {
-            crate::text::wrap_text(&cell.content, available_width, font_size)
-        }
-    } else {
-        // Split by newlines even when wrapping is off, to handle embedded newlines
-        cell.content.split('\n').map(|s| s.to_string()).collect()
+    let lines = match line_range {
+        Some(range) => &lines[range],
+        None => lines,
     };
+    if lines.is_empty() {
+        return Vec::new();
+    }
 
     // Calculate line height
     let line_height = font_size * DEFAULT_LINE_HEIGHT_MULTIPLIER;
@@ -742,57 +931,12 @@ fn draw_cell_text_operations(
     // Begin text object
     operations.push(Operation::new("BT", vec![]));
 
-    // Determine which font resource name to use:
-    // 1. Cell's embedded_font_resource_name (if set)
-    // 2. Table style's embedded_font_resource_name_bold when bold (if set)
-    // 3. Table style's embedded_font_resource_name (if set)
-    // 4. Type1 font mapping (backward compatible)
-    let embedded_font_name = cell
-        .style
-        .as_ref()
-        .and_then(|s| s.embedded_font_resource_name.as_deref())
-        .or_else(|| {
-            if is_bold {
-                table
-                    .style
-                    .embedded_font_resource_name_bold
-                    .as_deref()
-                    .or(table.style.embedded_font_resource_name.as_deref())
-            } else {
-                table.style.embedded_font_resource_name.as_deref()
-            }
-        });
-
-    let use_encoded_text = embedded_font_name.is_some() && metrics.is_some();
-
-    let font_resource_name: String = if let Some(efn) = embedded_font_name {
-        efn.to_string()
-    } else {
-        // Type1 font mapping
-        let base_font_name = cell
-            .style
-            .as_ref()
-            .and_then(|s| s.font_name.as_ref())
-            .map(|s| s.as_str())
-            .unwrap_or(&table.style.font_name);
-
-        if is_bold {
-            match base_font_name {
-                "Helvetica" => "F1-Bold",
-                "Courier" => "F2-Bold",
-                "Times-Roman" => "F3-Bold",
-                _ => "F1-Bold",
-            }
-        } else {
-            match base_font_name {
-                "Helvetica" => "F1",
-                "Courier" => "F2",
-                "Times-Roman" => "F3",
-                _ => "F1",
-            }
-        }
-        .to_string()
-    };
+    // Use the embedded font if one is set, else the standard Type1 font for
+    // the cell's font name
+    let embedded_font_name = embedded_font_name(table, cell);
+    let standard_font = StandardFont::for_cell(table, cell);
+    let encoding_metrics = metrics_for_cell(table, cell).filter(|_| embedded_font_name.is_some());
+    let font_resource_name = embedded_font_name.unwrap_or(standard_font.resource_name());
 
     operations.push(Operation::new(
         "Tf",
@@ -817,12 +961,10 @@ fn draw_cell_text_operations(
 
     // Draw each line of text
     for (line_idx, line) in lines.iter().enumerate() {
-        let estimated_text_width = measure_text_width(line, font_size, metrics);
-
         let text_x = match alignment {
             Alignment::Left => x + padding.left,
-            Alignment::Center => x + width / 2.0 - estimated_text_width / 2.0,
-            Alignment::Right => x + width - padding.right - estimated_text_width,
+            Alignment::Center => x + width / 2.0 - line.width / 2.0,
+            Alignment::Right => x + width - padding.right - line.width,
         };
 
         let text_y = first_line_y - (line_idx as f32 * line_height);
@@ -830,8 +972,7 @@ fn draw_cell_text_operations(
         if line_idx == 0 {
             operations.push(Operation::new("Td", vec![text_x.into(), text_y.into()]));
         } else {
-            let prev_line = &lines[line_idx - 1];
-            let prev_width = measure_text_width(prev_line, font_size, metrics);
+            let prev_width = lines[line_idx - 1].width;
             let prev_x = match alignment {
                 Alignment::Left => x + padding.left,
                 Alignment::Center => x + width / 2.0 - prev_width / 2.0,
@@ -843,19 +984,15 @@ fn draw_cell_text_operations(
             operations.push(Operation::new("Td", vec![dx.into(), dy.into()]));
         }
 
-        // Show text: use glyph ID encoding for embedded fonts, string literal for Type1
-        if use_encoded_text {
-            let encoded_bytes = metrics.unwrap().encode_text(line);
-            operations.push(Operation::new(
-                "Tj",
-                vec![Object::String(encoded_bytes, StringFormat::Hexadecimal)],
-            ));
-        } else {
-            operations.push(Operation::new(
-                "Tj",
-                vec![Object::string_literal(line.clone())],
-            ));
-        }
+        // Show text: use glyph ID encoding for embedded fonts, the standard
+        // font's single-byte encoding for Type1
+        let text = match encoding_metrics {
+            Some(metrics) => {
+                Object::String(metrics.encode_text(&line.text), StringFormat::Hexadecimal)
+            }
+            None => Object::String(standard_font.encode_text(&line.text), StringFormat::Literal),
+        };
+        operations.push(Operation::new("Tj", vec![text]));
     }
 
     // End text object
@@ -868,15 +1005,20 @@ fn draw_cell_text_operations(
 fn draw_cell_text(
     cell: &crate::table::Cell,
     table: &Table,
-    x: f32,
-    y: f32,
-    width: f32,
-    height: f32,
+    bounds: Rect,
+    lines: &[TextLine],
+    line_range: Option<Range<usize>>,
 ) -> Result<Vec<Object>> {
     // Convert text drawing operations to the flat Object list and
     // wrap them with a clipping path equal to the cell bounds so that
     // text never renders outside the cell.
-    let ops = draw_cell_text_operations(cell, table, x, y, width, height);
+    let ops = draw_cell_text_operations(cell, table, bounds, lines, line_range);
+    let Rect {
+        x,
+        y,
+        width,
+        height,
+    } = bounds;
     let mut objects = Vec::new();
 
     // Save graphics state
@@ -884,7 +1026,7 @@ fn draw_cell_text(
     // Define clipping rectangle (PDF uses lower-left origin)
     objects.push(Object::Name("re".as_bytes().to_vec()));
     objects.push(x.into());
-    objects.push((y - height).into());
+    objects.push(y.into());
     objects.push(width.into());
     objects.push(height.into());
     // Set clip and end path
@@ -926,7 +1068,18 @@ pub fn add_operations_to_page(
     trace!("Raw operations: {:?}", operations);
 
     // Convert operations to Content using the shared utility
-    let content_ops = objects_to_operations(&operations);
+    let mut content_ops = objects_to_operations(&operations);
+
+    // Tables are placed in the visible page coordinates; map them onto rotated
+    // or offset pages
+    if let Some(matrix) = PageBox::of_page(doc, page_id)?.transform() {
+        content_ops.insert(0, Operation::new("q", vec![]));
+        content_ops.insert(
+            1,
+            Operation::new("cm", matrix.iter().map(|&v| v.into()).collect()),
+        );
+        content_ops.push(Operation::new("Q", vec![]));
+    }
     let content = Content {
         operations: content_ops.clone(),
     };
@@ -953,7 +1106,7 @@ pub fn draw_table_paginated(
     table: &Table,
     layout: &TableLayout,
     position: (f32, f32),
-    mut hook: Option<&mut dyn TaggedCellHook>,
+    hook: Option<&mut dyn TaggedCellHook>,
     image_registry: Option<&ImageXObjects>,
 ) -> Result<PagedTableResult> {
     debug!(
@@ -962,129 +1115,971 @@ pub fn draw_table_paginated(
         table.header_rows
     );
 
-    // Get page dimensions
-    let page_height = table.style.page_height.unwrap_or(A4_HEIGHT); // A4 default
-    let top_margin = table.style.top_margin;
-    let bottom_margin = table.style.bottom_margin;
+    let page = PageBox::of_page(doc, start_page_id)?.visible();
+    if crate::bands::is_banded(table, layout, &page) {
+        return crate::bands::draw_table_banded(
+            doc,
+            start_page_id,
+            table,
+            layout,
+            position,
+            hook,
+            image_registry,
+        );
+    }
 
-    let (start_x, start_y) = position;
-    let _available_height = start_y - bottom_margin;
+    let (plan, page_ids) = plan_on_new_pages(
+        doc,
+        start_page_id,
+        None,
+        table,
+        layout,
+        table.page_break_rows.as_deref(),
+        position,
+        0,
+    )?;
+    draw_planned_pages(doc, &page_ids, table, &plan, hook, image_registry)
+}
 
-    // Track pages used
+/// Plan every page of a table starting at `position` on `start_page_id`,
+/// creating continuation pages as the plan needs them.
+///
+/// `page_break_rows` generates the rows at page breaks (usually the table's
+/// own). `first_page_index` is the index of `start_page_id` among all of the
+/// table's pages, from which the page factory's page indexes count on.
+/// `start_region` is the region of a start page made by the page factory, on
+/// which the table starts at the top instead of at `position`.
+/// Returns the plan with the pages it is drawn on.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn plan_on_new_pages<'a>(
+    doc: &mut Document,
+    start_page_id: ObjectId,
+    start_region: Option<PageBox>,
+    table: &Table,
+    layout: &'a TableLayout,
+    page_break_rows: Option<&dyn PageBreakRows>,
+    position: (f32, f32),
+    first_page_index: usize,
+) -> Result<(TablePlan<'a>, Vec<ObjectId>)> {
+    let page = PageBox::of_page(doc, start_page_id)?.visible();
     let mut page_ids = vec![start_page_id];
-    let mut current_page_id = start_page_id;
+    let plan = plan_pages(
+        table,
+        layout,
+        page_break_rows,
+        &page,
+        start_region,
+        position,
+        &mut |page_index| {
+            let previous_page_id = page_ids[page_ids.len() - 1];
+            match &table.page_factory {
+                Some(factory) => {
+                    let page = factory.continuation_page(
+                        doc,
+                        previous_page_id,
+                        first_page_index + page_index,
+                    )?;
+                    page_ids.push(page.page_id);
+                    Ok(Some(page.region))
+                }
+                None => {
+                    page_ids.push(create_new_page(doc, previous_page_id)?);
+                    Ok(None)
+                }
+            }
+        },
+    )?;
+    if plan.starts_on_new_page {
+        page_ids.remove(0);
+    }
+    Ok((plan, page_ids))
+}
+
+/// Plan the pages of a table starting at `position` on a page with the given
+/// visible box.
+///
+/// With a `start_region`, the table starts at the top of that region instead,
+/// aligned within it like on continuation pages. `new_page` is called with the
+/// index of every continuation page the table needs and returns its region,
+/// or `None` for the margins of the start page.
+pub(crate) fn plan_pages<'a>(
+    table: &Table,
+    layout: &'a TableLayout,
+    page_break_rows: Option<&dyn PageBreakRows>,
+    page: &PageBox,
+    start_region: Option<PageBox>,
+    position: (f32, f32),
+    new_page: &mut dyn FnMut(usize) -> Result<Option<PageBox>>,
+) -> Result<TablePlan<'a>> {
+    let page_height = table.style.page_height.unwrap_or(page.height());
+
+    // Area of the page the table is drawn in: within the margins of the start
+    // page, and as given by the page factory on continuation pages
+    let mut region = start_region.unwrap_or(PageBox {
+        left: page.left + table.style.left_margin,
+        bottom: table.style.bottom_margin,
+        right: page.right - table.style.right_margin,
+        top: page_height - table.style.top_margin,
+        rotation: 0,
+    });
+
+    let (start_x, start_y) = match start_region {
+        Some(region) => (
+            region.aligned_x(
+                table.style.horizontal_alignment,
+                layout.total_width,
+                position.0,
+            ),
+            region.top,
+        ),
+        None => (
+            page.table_x(&table.style, layout.total_width, position.0),
+            position.1,
+        ),
+    };
+    let mut page_x = start_x;
+    let mut page_y = start_y;
+
+    // Track pages used
+    let mut page_count = 1;
+    let mut starts_on_new_page = false;
+    let mut planned_pages: Vec<PagePlan> = Vec::new();
     let mut current_y = start_y;
-    let mut rows_on_current_page = Vec::new();
+    let mut rows_on_current_page: Vec<PageRow> = Vec::new();
+    let mut repeated_headers_on_page = 0;
+    let mut split_rows = Vec::new();
+
+    // Footer rows close every page, so body rows stop above the space they need
+    let footer_start = table.rows.len() - table.footer_rows.min(table.rows.len());
+    let final_footer_start = footer_start - table.final_footer_rows.min(footer_start);
+    let footer_height: f32 = layout.row_heights[footer_start..].iter().sum();
+    let mut body_limit = region.bottom + footer_height;
+    let footer_rows: Vec<PageRow> = (footer_start..table.rows.len())
+        .map(|row| PageRow::whole(row, layout))
+        .collect();
+
+    // Body rows placed so far, and rows generated at page breaks (running subtotals)
+    let body_rows = table.header_rows..final_footer_start;
+    let mut rows_placed: Vec<usize> = Vec::new();
+    let mut page_first_placed = 0;
+    let mut brought_forward: Option<GeneratedRow> = None;
+    // Room kept for the carried forward row: a single line, until a taller
+    // row is generated at a page break
+    let mut carried_reserve = if page_break_rows.is_some() {
+        table.style.default_font_size * DEFAULT_LINE_HEIGHT_MULTIPLIER
+            + table.style.padding.top
+            + table.style.padding.bottom
+    } else {
+        0.0
+    };
+
+    // Height left for body rows on a continuation page (after repeated headers)
+    let repeated_header_height: f32 = if table.style.repeat_headers {
+        layout.row_heights[..table.header_rows.min(table.rows.len())]
+            .iter()
+            .sum()
+    } else {
+        0.0
+    };
+    let mut continuation_capacity = region.top - body_limit - repeated_header_height;
+    let mut first_page_capacity = region.top - body_limit;
+    let mut on_start_page = true;
 
-    // Process all rows
+    // Rows waiting to be placed: a group tied together by rowspans, or the
+    // remainder of a row split at the previous page break
+    let mut pending: Vec<PageRow> = Vec::new();
     let mut row_idx = 0;
-    while row_idx < table.rows.len() {
-        let row_height = layout.row_heights[row_idx];
-
-        // Check if this row fits on the current page
-        if current_y - row_height < bottom_margin && !rows_on_current_page.is_empty() {
-            // Draw rows accumulated for current page
-            draw_rows_subset(
-                doc,
-                current_page_id,
+    // Planning state when the current page started, to plan the page again
+    // if its carried forward row is taller than the room kept for it
+    let mut page_start = PageStart {
+        row_idx,
+        pending: pending.clone(),
+        current_y,
+        rows_on_page: rows_on_current_page.clone(),
+        rows_placed: rows_placed.len(),
+        split_rows: split_rows.len(),
+    };
+    loop {
+        if pending.is_empty() {
+            if row_idx >= footer_start {
+                break;
+            }
+            let capacity = if row_idx < table.header_rows {
+                first_page_capacity
+            } else {
+                continuation_capacity
+            } - carried_reserve
+                - generated_row_height(&brought_forward);
+            let group_end = page_group_end(
                 table,
                 layout,
-                &rows_on_current_page,
-                (
-                    start_x,
-                    if page_ids.len() == 1 {
-                        start_y
-                    } else {
-                        page_height - top_margin
-                    },
-                ),
-                &mut hook,
-                image_registry,
-            )?;
-
-            // Create new page
-            current_page_id = create_new_page(doc, current_page_id)?;
-            page_ids.push(current_page_id);
-
-            // Reset position for new page
-            current_y = page_height - top_margin;
-            rows_on_current_page.clear();
-
-            // Add header rows to new page if configured
-            if table.style.repeat_headers && table.header_rows > 0 && row_idx >= table.header_rows {
-                for header_idx in 0..table.header_rows {
-                    rows_on_current_page.push(header_idx);
-                    current_y -= layout.row_heights[header_idx];
+                row_idx,
+                final_footer_start..footer_start,
+                capacity,
+            );
+            pending.extend((row_idx..group_end).map(|row| PageRow::whole(row, layout)));
+            row_idx = group_end;
+        }
+
+        let pending_height: f32 = pending.iter().map(|r| r.height).sum();
+        let page_has_rows = rows_on_current_page.len() > repeated_headers_on_page;
+
+        let body_rows_on_page = rows_placed.len() - page_first_placed;
+        let pending_body_rows = pending
+            .iter()
+            .filter(|r| !r.continued && body_rows.contains(&r.row))
+            .count();
+
+        // The carried forward row is only needed if the table continues after
+        // these rows
+        let limit = if row_idx >= footer_start {
+            body_limit
+        } else {
+            body_limit + carried_reserve
+        };
+
+        // Rows forced onto a new page: by a page break flag, or once the page
+        // holds the maximum number of data rows
+        let forced_break = body_rows_on_page > 0
+            && ((!pending[0].continued && table.rows[pending[0].row].page_break_before)
+                || table
+                    .max_rows_per_page
+                    .is_some_and(|max| body_rows_on_page + pending_body_rows > max));
+
+        // Check if the pending rows fit on the current page
+        if forced_break || current_y - pending_height < limit {
+            // Only the last row of a group may be split, and only below header
+            // rows kept with it (a lone row is the common case)
+            let (lead, last) = pending.split_at(pending.len() - 1);
+            let split = if table.style.split_rows
+                && !forced_break
+                && lead.iter().all(|r| r.row < table.header_rows)
+            {
+                let lead_height: f32 = lead.iter().map(|r| r.height).sum();
+                split_page_row(table, layout, &last[0], current_y - limit - lead_height)
+            } else {
+                None
+            };
+
+            // Rather than overflow the start page, move the table to a new page
+            // if its first rows fit there
+            let move_table_start =
+                on_start_page && !page_has_rows && pending_height <= first_page_capacity;
+
+            if split.is_some() || page_has_rows || move_table_start {
+                if let Some((head, tail)) = split {
+                    if !head.continued {
+                        split_rows.push(head.row);
+                        if body_rows.contains(&head.row) {
+                            rows_placed.push(head.row);
+                        }
+                    }
+                    pending.pop();
+                    rows_on_current_page.append(&mut pending);
+                    rows_on_current_page.push(head);
+                    pending = vec![tail];
+                }
+
+                if rows_on_current_page.is_empty() {
+                    // Nothing of the table on this page: start it on the next one
+                    page_count -= 1;
+                    starts_on_new_page = true;
+                } else {
+                    let page_index = page_count - 1;
+                    let rows_on_page = rows_placed.len() - page_first_placed;
+                    let carried_forward = page_break_row(
+                        table,
+                        layout,
+                        page_break_rows,
+                        page_index,
+                        &rows_placed,
+                        rows_on_page,
+                        true,
+                    )?;
+
+                    // Its totals include every row on the page, which may
+                    // make it taller than the room kept for it. Plan the page
+                    // again keeping room for a row this tall.
+                    let carried_height = generated_row_height(&carried_forward);
+                    let page_rows_height: f32 =
+                        rows_on_current_page.iter().map(|r| r.height).sum::<f32>()
+                            + generated_row_height(&brought_forward);
+                    if carried_height > carried_reserve {
+                        carried_reserve = carried_height;
+                        if page_y - page_rows_height - carried_height < body_limit {
+                            row_idx = page_start.row_idx;
+                            pending = page_start.pending.clone();
+                            current_y = page_start.current_y;
+                            rows_on_current_page = page_start.rows_on_page.clone();
+                            rows_placed.truncate(page_start.rows_placed);
+                            split_rows.truncate(page_start.split_rows);
+                            continue;
+                        }
+                    }
+
+                    let next_brought_forward = page_break_row(
+                        table,
+                        layout,
+                        page_break_rows,
+                        page_index,
+                        &rows_placed,
+                        rows_on_page,
+                        false,
+                    )?;
+
+                    // Plan rows accumulated for current page, closed by the footer rows
+                    let body = rows_on_current_page.split_off(repeated_headers_on_page);
+                    planned_pages.push(PagePlan::new(
+                        (page_x, page_y),
+                        repeated_headers_on_page,
+                        row_span(&body, &[]),
+                        vec![
+                            PageSection::Rows(std::mem::take(&mut rows_on_current_page)),
+                            PageSection::Generated(brought_forward.take().map(Box::new)),
+                            PageSection::Rows(body),
+                            PageSection::Generated(carried_forward.map(Box::new)),
+                            PageSection::Rows(footer_rows.clone()),
+                        ],
+                        region,
+                        layout,
+                    ));
+                    brought_forward = next_brought_forward;
                 }
+
+                // Create new page
+                if let Some(new_region) = new_page(page_count)? {
+                    region = new_region;
+                    page_x = region.aligned_x(
+                        table.style.horizontal_alignment,
+                        layout.total_width,
+                        start_x,
+                    );
+                    body_limit = region.bottom + footer_height;
+                    continuation_capacity = region.top - body_limit - repeated_header_height;
+                    first_page_capacity = region.top - body_limit;
+                }
+                page_count += 1;
+                on_start_page = false;
+
+                // Reset position for new page
+                current_y = region.top;
+                page_y = region.top;
+                rows_on_current_page.clear();
+                repeated_headers_on_page = 0;
+                page_first_placed = rows_placed.len();
+
+                // Add header rows to new page if configured
+                if table.style.repeat_headers
+                    && table.header_rows > 0
+                    && pending[0].row >= table.header_rows
+                {
+                    for header_idx in 0..table.header_rows {
+                        rows_on_current_page.push(PageRow::whole(header_idx, layout));
+                        current_y -= layout.row_heights[header_idx];
+                    }
+                    repeated_headers_on_page = table.header_rows;
+                }
+
+                current_y -= generated_row_height(&brought_forward);
+                page_start = PageStart {
+                    row_idx,
+                    pending: pending.clone(),
+                    current_y,
+                    rows_on_page: rows_on_current_page.clone(),
+                    rows_placed: rows_placed.len(),
+                    split_rows: split_rows.len(),
+                };
+                continue;
             }
         }
 
-        // Add current row to page
-        rows_on_current_page.push(row_idx);
-        current_y -= row_height;
-        row_idx += 1;
+        // Add pending rows to page (overflowing if they cannot fit on an empty page)
+        current_y -= pending_height;
+        rows_placed.extend(
+            pending
+                .iter()
+                .filter(|r| !r.continued && body_rows.contains(&r.row))
+                .map(|r| r.row),
+        );
+        rows_on_current_page.append(&mut pending);
     }
 
-    // Draw remaining rows on last page
-    if !rows_on_current_page.is_empty() {
-        let page_y = if page_ids.len() == 1 {
-            start_y
-        } else {
-            page_height - top_margin
+    // Fill the last page down to the footer rows (or bottom margin)
+    let final_footer_entry = rows_on_current_page
+        .iter()
+        .skip(repeated_headers_on_page)
+        .position(|r| r.row >= final_footer_start)
+        .map_or(rows_on_current_page.len(), |idx| {
+            idx + repeated_headers_on_page
+        });
+    let remaining = current_y - body_limit;
+    let mut filler = None;
+    if remaining > 0.0 {
+        match &table.page_fill {
+            Some(PageFill::FillerRows(template)) => {
+                filler = filler_rows(table, layout, template, remaining)?;
+                current_y -= generated_row_height(&filler);
+            }
+            Some(PageFill::StretchLastRow) => {
+                let last_data_row = rows_on_current_page
+                    [repeated_headers_on_page..final_footer_entry]
+                    .iter_mut()
+                    .rev()
+                    .find(|r| body_rows.contains(&r.row));
+                if let Some(last_data_row) = last_data_row {
+                    last_data_row.height += remaining;
+                    current_y -= remaining;
+                }
+            }
+            None => {}
+        }
+    }
+
+    // Close the last page with the footer rows
+    current_y -= footer_height;
+
+    // Plan remaining rows on last page
+    let final_footer = rows_on_current_page.split_off(final_footer_entry);
+    let body = rows_on_current_page.split_off(repeated_headers_on_page);
+    planned_pages.push(PagePlan::new(
+        (page_x, page_y),
+        repeated_headers_on_page,
+        row_span(&body, &final_footer),
+        vec![
+            PageSection::Rows(rows_on_current_page),
+            PageSection::Generated(brought_forward.map(Box::new)),
+            PageSection::Rows(body),
+            PageSection::Generated(filler.map(Box::new)),
+            PageSection::Rows(final_footer),
+            PageSection::Rows(footer_rows),
+        ],
+        region,
+        layout,
+    ));
+    debug_assert_eq!(planned_pages.len(), page_count);
+
+    Ok(TablePlan {
+        pages: planned_pages,
+        starts_on_new_page,
+        final_position: (page_x, current_y),
+        split_rows,
+        page: *page,
+        layout: Cow::Borrowed(layout),
+    })
+}
+
+/// Planning state at the top of a page, before any of its body rows are placed
+struct PageStart {
+    row_idx: usize,
+    pending: Vec<PageRow>,
+    current_y: f32,
+    rows_on_page: Vec<PageRow>,
+    rows_placed: usize,
+    split_rows: usize,
+}
+
+/// Rows spanned by two consecutive runs of page rows
+fn row_span(rows: &[PageRow], then: &[PageRow]) -> Range<usize> {
+    match (rows.first().or(then.first()), then.last().or(rows.last())) {
+        (Some(first), Some(last)) => first.row..last.row + 1,
+        _ => 0..0,
+    }
+}
+
+/// Draw a planned table, creating the continuation pages it needs after
+/// `start_page_id`
+pub(crate) fn draw_table_plan(
+    doc: &mut Document,
+    start_page_id: ObjectId,
+    table: &Table,
+    plan: &TablePlan,
+    hook: Option<&mut dyn TaggedCellHook>,
+    image_registry: Option<&ImageXObjects>,
+) -> Result<PagedTableResult> {
+    let region_mismatch = |page_index: usize| {
+        crate::error::TableError::LayoutError(format!(
+            "Page {page_index} of the table does not match the region it was planned for"
+        ))
+    };
+    if PageBox::of_page(doc, start_page_id)?.visible() != plan.page {
+        return Err(region_mismatch(0));
+    }
+
+    let mut page_ids = vec![start_page_id];
+    let first_new_page = if plan.starts_on_new_page { 0 } else { 1 };
+    for page_index in first_new_page..plan.pages.len() {
+        let previous_page_id = page_ids[page_ids.len() - 1];
+        let page_id = match &table.page_factory {
+            Some(factory) => {
+                let page = factory.continuation_page(doc, previous_page_id, page_index)?;
+                if page.region != plan.pages[page_index].region {
+                    return Err(region_mismatch(page_index));
+                }
+                page.page_id
+            }
+            None => {
+                // Continuation pages copy the start page and its margins
+                if plan.pages[page_index].region != plan.pages[0].region {
+                    return Err(region_mismatch(page_index));
+                }
+                create_new_page(doc, previous_page_id)?
+            }
         };
+        page_ids.push(page_id);
+    }
+    if plan.starts_on_new_page {
+        page_ids.remove(0);
+    }
 
-        draw_rows_subset(
+    draw_planned_pages(doc, &page_ids, table, plan, hook, image_registry)
+}
+
+/// Draw the pages of a plan on `page_ids`, one per planned page, then the
+/// decorations that need the page count
+pub(crate) fn draw_planned_pages(
+    doc: &mut Document,
+    page_ids: &[ObjectId],
+    table: &Table,
+    plan: &TablePlan,
+    mut hook: Option<&mut dyn TaggedCellHook>,
+    image_registry: Option<&ImageXObjects>,
+) -> Result<PagedTableResult> {
+    for (&page_id, page) in page_ids.iter().zip(&plan.pages) {
+        draw_page_sections(
             doc,
-            current_page_id,
+            page_id,
             table,
-            layout,
-            &rows_on_current_page,
-            (start_x, page_y),
+            &plan.layout,
+            &page.sections,
+            page.position,
             &mut hook,
             image_registry,
         )?;
     }
 
-    Ok(PagedTableResult {
+    let mut rows = Vec::new();
+    let mut cells = Vec::new();
+    for (&page_id, page) in page_ids.iter().zip(&plan.pages) {
+        place_rows(
+            page_id,
+            page.position.0,
+            &page.placed_rows,
+            &plan.layout,
+            &mut rows,
+            &mut cells,
+        );
+    }
+
+    let result = PagedTableResult {
         total_pages: page_ids.len(),
-        page_ids,
-        final_position: (start_x, current_y),
-    })
+        page_ids: page_ids.to_vec(),
+        final_position: plan.final_position,
+        split_rows: plan.split_rows.clone(),
+        table_bounds: plan.pages.iter().map(|page| page.bounds).collect(),
+        rows,
+        cells,
+    };
+    let regions: Vec<PageBox> = plan.pages.iter().map(|page| page.region).collect();
+    decorate_pages(doc, table, &result, &regions, hook.is_some())?;
+    Ok(result)
 }
 
-/// Create a new page with the same configuration as the source page
-fn create_new_page(doc: &mut Document, source_page_id: ObjectId) -> Result<ObjectId> {
-    debug!("Creating new page for table continuation");
+/// Result of drawing a whole table on one page at `position`
+pub(crate) fn single_page_result(
+    page_id: ObjectId,
+    layout: &TableLayout,
+    position: (f32, f32),
+) -> PagedTableResult {
+    let mut y = position.1;
+    let placed_rows: Vec<PlannedRow> = layout
+        .row_heights
+        .iter()
+        .enumerate()
+        .map(|(row, &height)| {
+            let placed = PlannedRow {
+                row,
+                y,
+                height,
+                continued: false,
+            };
+            y -= height;
+            placed
+        })
+        .collect();
+
+    let mut rows = Vec::new();
+    let mut cells = Vec::new();
+    place_rows(
+        page_id,
+        position.0,
+        &placed_rows,
+        layout,
+        &mut rows,
+        &mut cells,
+    );
+    PagedTableResult {
+        page_ids: vec![page_id],
+        total_pages: 1,
+        final_position: (position.0, y),
+        split_rows: Vec::new(),
+        table_bounds: vec![Rect {
+            x: position.0,
+            y,
+            width: layout.total_width,
+            height: position.1 - y,
+        }],
+        rows,
+        cells,
+    }
+}
 
-    // Get the parent Pages object from the source page
-    let pages_id = if let Ok(Object::Dictionary(page_dict)) = doc.get_object(source_page_id) {
-        if let Ok(Object::Reference(pages_ref)) = page_dict.get(b"Parent") {
-            *pages_ref
-        } else {
-            return Err(crate::error::TableError::DrawingError(
-                "Could not find parent Pages object".to_string(),
-            ));
+/// Rectangles of the rows placed on a page and of the cells they declare.
+///
+/// A cell spanning rows covers the following rows placed right after it on
+/// the same page.
+fn place_rows(
+    page_id: ObjectId,
+    x: f32,
+    placed_rows: &[PlannedRow],
+    layout: &TableLayout,
+    rows: &mut Vec<PlacedRow>,
+    cells: &mut Vec<PlacedCell>,
+) {
+    let column_x: Vec<f32> = std::iter::once(0.0)
+        .chain(layout.column_widths.iter().scan(0.0, |acc, &w| {
+            *acc += w;
+            Some(*acc)
+        }))
+        .collect();
+
+    for (idx, placed) in placed_rows.iter().enumerate() {
+        rows.push(PlacedRow {
+            page_id,
+            row: placed.row,
+            rect: Rect {
+                x,
+                y: placed.y - placed.height,
+                width: layout.total_width,
+                height: placed.height,
+            },
+            continued: placed.continued,
+        });
+
+        for placement in layout.grid.placements_in_row(placed.row) {
+            let spanned = placed_rows[idx..]
+                .iter()
+                .enumerate()
+                .take_while(|(offset, r)| {
+                    *offset < placement.rowspan && r.row == placed.row + offset
+                })
+                .last()
+                .map_or(placed, |(_, r)| r);
+            let bottom = spanned.y - spanned.height;
+            cells.push(PlacedCell {
+                page_id,
+                row: placed.row,
+                column: placement.col,
+                rect: Rect {
+                    x: x + column_x[placement.col],
+                    y: bottom,
+                    width: column_x[placement.col + placement.colspan] - column_x[placement.col],
+                    height: placed.y - bottom,
+                },
+                continued: placed.continued,
+            });
         }
-    } else {
-        return Err(crate::error::TableError::DrawingError(
-            "Invalid page object".to_string(),
-        ));
+    }
+}
+
+/// Draw the table's page decorations, once every page of the table is known
+pub(crate) fn decorate_pages(
+    doc: &mut Document,
+    table: &Table,
+    result: &PagedTableResult,
+    regions: &[PageBox],
+    tagged: bool,
+) -> Result<()> {
+    let Some(decorator) = table.page_decorator.as_deref() else {
+        return Ok(());
     };
+    for (page_index, ((&page_id, &table_bounds), &region)) in result
+        .page_ids
+        .iter()
+        .zip(&result.table_bounds)
+        .zip(regions)
+        .enumerate()
+    {
+        let page = DecoratedPage {
+            page_id,
+            page_index,
+            total_pages: result.total_pages,
+            table_bounds,
+            page: PageBox::of_page(doc, page_id)?.visible(),
+            region,
+        };
+        let operations = operations_to_objects(decorator.decorate(&page));
+        if operations.is_empty() {
+            continue;
+        }
+        // Headers, footers and page numbers are pagination artifacts
+        let operations = if tagged {
+            wrap_objects_as_artifact(operations)
+        } else {
+            operations
+        };
+        add_operations_to_page(doc, page_id, operations)?;
+    }
+    Ok(())
+}
 
-    // Get MediaBox and Resources from source page
-    let (media_box, resources_id) =
-        if let Ok(Object::Dictionary(page_dict)) = doc.get_object(source_page_id) {
-            let media_box = page_dict.get(b"MediaBox").ok().cloned();
-            let resources = page_dict.get(b"Resources").ok().cloned();
-            (media_box, resources)
+/// End (exclusive) of the rows starting at `start` that must stay on one page.
+///
+/// Rowspans, `Row::keep_with_next`, `Table::keep_together` and header rows
+/// (kept with the first body rows so they are never left alone at the bottom of
+/// a page) all tie rows together. When the whole group exceeds `capacity`, the
+/// explicit keep rules are dropped, then header rows, then rowspans. Rows of
+/// `final_footer` stay together as well. A row with `Row::page_break_before`
+/// always starts a new group.
+fn page_group_end(
+    table: &Table,
+    layout: &TableLayout,
+    start: usize,
+    final_footer: Range<usize>,
+    capacity: f32,
+) -> usize {
+    let fits = |end: usize| layout.row_heights[start..end].iter().sum::<f32>() <= capacity;
+    if start >= final_footer.start {
+        return if fits(final_footer.end) {
+            final_footer.end
         } else {
-            (None, None)
+            start + 1
         };
+    }
+
+    let extend = |keep_rules: bool, keep_headers: bool| -> usize {
+        let mut end = layout.grid.group_end(start);
+        while end < final_footer.start {
+            let last = end - 1;
+            let tied = (keep_headers && last < table.header_rows)
+                || (keep_rules
+                    && (table.rows[last].keep_with_next
+                        || table
+                            .keep_together
+                            .iter()
+                            .any(|group| group.start <= last && last + 1 < group.end)));
+            if !tied || table.rows[end].page_break_before {
+                break;
+            }
+            end = layout.grid.group_end(end);
+        }
+        // A group reaching the final footer takes all of it
+        if end > final_footer.start {
+            final_footer.end
+        } else {
+            end
+        }
+    };
+
+    [
+        extend(true, true),
+        extend(false, true),
+        extend(false, false),
+    ]
+    .into_iter()
+    .find(|&end| fits(end))
+    .unwrap_or(start + 1)
+}
+
+/// Rows generated while paginating (page break rows, filler rows), laid out
+/// as a standalone table on the table's columns
+#[derive(Debug)]
+pub(crate) struct GeneratedRow {
+    table: Table,
+    layout: TableLayout,
+}
+
+fn generated_row_height(row: &Option<GeneratedRow>) -> f32 {
+    row.as_ref().map_or(0.0, |g| g.layout.total_height)
+}
+
+/// Lay out generated rows with the table's style, fonts and column widths
+fn generated_rows(table: &Table, layout: &TableLayout, rows: Vec<Row>) -> Result<GeneratedRow> {
+    let generated = Table {
+        rows,
+        style: table.style.clone(),
+        column_widths: Some(
+            layout
+                .column_widths
+                .iter()
+                .map(|w| ColumnWidth::Pixels(*w))
+                .collect(),
+        ),
+        total_width: Some(layout.total_width),
+        font_metrics: table.font_metrics.clone(),
+        bold_font_metrics: table.bold_font_metrics.clone(),
+        italic_font_metrics: table.italic_font_metrics.clone(),
+        bold_italic_font_metrics: table.bold_italic_font_metrics.clone(),
+        ..Table::new()
+    };
+    if generated.column_count() != layout.column_widths.len() {
+        return Err(crate::error::TableError::InvalidTable(format!(
+            "Generated row covers {} columns, expected {}",
+            generated.column_count(),
+            layout.column_widths.len()
+        )));
+    }
+    let generated_layout = crate::layout::calculate_layout(&generated)?;
+    Ok(GeneratedRow {
+        table: generated,
+        layout: generated_layout,
+    })
+}
+
+/// Ask `page_break_rows` for the carried forward (or brought forward) row and
+/// lay it out on the table's columns.
+fn page_break_row(
+    table: &Table,
+    layout: &TableLayout,
+    page_break_rows: Option<&dyn PageBreakRows>,
+    page_index: usize,
+    rows_placed: &[usize],
+    rows_on_page: usize,
+    carried: bool,
+) -> Result<Option<GeneratedRow>> {
+    let Some(generator) = page_break_rows else {
+        return Ok(None);
+    };
+    let page_break = PageBreak {
+        table,
+        page_index,
+        rows_placed,
+        rows_on_page,
+        grid: &layout.grid,
+    };
+    let row = if carried {
+        generator.carried_forward(&page_break)
+    } else {
+        generator.brought_forward(&page_break)
+    };
+    row.map(|row| generated_rows(table, layout, vec![row]))
+        .transpose()
+}
+
+/// As many copies of `template` as fit in `available` height
+fn filler_rows(
+    table: &Table,
+    layout: &TableLayout,
+    template: &Row,
+    available: f32,
+) -> Result<Option<GeneratedRow>> {
+    let row_height = generated_rows(table, layout, vec![template.clone()])?
+        .layout
+        .total_height;
+    let count = (available / row_height + 0.001).floor() as usize;
+    if row_height <= 0.0 || count == 0 {
+        return Ok(None);
+    }
+    generated_rows(table, layout, vec![template.clone(); count]).map(Some)
+}
+
+/// Split a row at the page edge, leaving `available` height for its first slice.
+///
+/// Each cell keeps as many of its text lines as fit in the first slice; the
+/// rest are carried into a continuation slice sized for the remaining lines.
+/// Returns `None` when the row cannot be split usefully: it belongs to a
+/// rowspan group, no line fits in `available`, or every line already fits.
+fn split_page_row(
+    table: &Table,
+    layout: &TableLayout,
+    page_row: &PageRow,
+    available: f32,
+) -> Option<(PageRow, PageRow)> {
+    let row = &table.rows[page_row.row];
+    let placements = layout.grid.placements_in_row(page_row.row);
+    if layout.grid.spans_into(page_row.row)
+        || placements.len() != row.cells.len()
+        || placements.iter().any(|p| p.rowspan > 1)
+    {
+        return None;
+    }
+
+    let mut head_lines = Vec::with_capacity(row.cells.len());
+    let mut tail_lines = Vec::with_capacity(row.cells.len());
+    // Tallest cell of the continuation slice, with its own padding
+    let mut tail_height: f32 = 0.0;
+
+    for placement in placements {
+        let cell = &row.cells[placement.cell];
+        let line_count = layout.text_lines(page_row.row, placement.cell).len();
+        let range = page_row.cell_lines(placement).unwrap_or(0..line_count);
+
+        let font_size = cell
+            .style
+            .as_ref()
+            .and_then(|s| s.font_size)
+            .unwrap_or(table.style.default_font_size);
+        let padding = cell
+            .style
+            .as_ref()
+            .and_then(|s| s.padding.as_ref())
+            .unwrap_or(&table.style.padding);
+        let line_height = font_size * DEFAULT_LINE_HEIGHT_MULTIPLIER;
+
+        let fitting = ((available - padding.top - padding.bottom) / line_height + 0.001)
+            .floor()
+            .max(0.0) as usize;
+        let split_at = (range.start + fitting).min(range.end);
+
+        tail_height = tail_height
+            .max((range.end - split_at) as f32 * line_height + padding.top + padding.bottom);
+        head_lines.push(range.start..split_at);
+        tail_lines.push(split_at..range.end);
+    }
+
+    let nothing_fits = head_lines.iter().all(|r| r.is_empty());
+    let everything_fits = tail_lines.iter().all(|r| r.is_empty());
+    if nothing_fits || everything_fits {
+        return None;
+    }
+
+    let min_height = table.style.default_font_size * DEFAULT_LINE_HEIGHT_MULTIPLIER;
+    let head = PageRow {
+        row: page_row.row,
+        height: available,
+        lines: Some(head_lines),
+        continued: page_row.continued,
+    };
+    let tail = PageRow {
+        row: page_row.row,
+        height: tail_height.max(min_height),
+        lines: Some(tail_lines),
+        continued: true,
+    };
+    trace!(
+        "Split row {} into {:.1}pt + {:.1}pt",
+        page_row.row, head.height, tail.height
+    );
+    Some((head, tail))
+}
+
+/// Create a new page with the same configuration as the source page, inserted
+/// right after it in the page tree
+pub(crate) fn create_new_page(doc: &mut Document, source_page_id: ObjectId) -> Result<ObjectId> {
+    debug!("Creating new page for table continuation");
+
+    // Get the page boxes and rotation, possibly inherited, and Resources from source page
+    let media_box = inherited_attribute(doc, source_page_id, b"MediaBox").cloned();
+    let crop_box = inherited_attribute(doc, source_page_id, b"CropBox").cloned();
+    let rotate = inherited_attribute(doc, source_page_id, b"Rotate").cloned();
+    let resources_id = if let Ok(Object::Dictionary(page_dict)) = doc.get_object(source_page_id) {
+        page_dict.get(b"Resources").ok().cloned()
+    } else {
+        None
+    };
 
     // Create new page dictionary
     let mut new_page_dict = dictionary! {
         "Type" => "Page",
-        "Parent" => pages_id,
     };
 
     if let Some(media_box) = media_box {
@@ -1097,190 +2092,96 @@ fn create_new_page(doc: &mut Document, source_page_id: ObjectId) -> Result<Objec
         );
     }
 
+    if let Some(crop_box) = crop_box {
+        new_page_dict.set("CropBox", crop_box);
+    }
+
+    if let Some(rotate) = rotate {
+        new_page_dict.set("Rotate", rotate);
+    }
+
     if let Some(resources) = resources_id {
         new_page_dict.set("Resources", resources);
     }
 
-    let new_page_id = doc.add_object(new_page_dict);
+    let new_page_id = insert_page_after(doc, source_page_id, new_page_dict)?;
 
-    // Add page to Pages kids array
-    if let Ok(Object::Dictionary(pages_dict)) = doc.get_object_mut(pages_id) {
-        if let Ok(Object::Array(kids)) = pages_dict.get_mut(b"Kids") {
-            kids.push(new_page_id.into());
-        }
+    trace!("Created new page {:?}", new_page_id);
+    Ok(new_page_id)
+}
 
-        // Update page count
-        if let Ok(Object::Integer(count)) = pages_dict.get(b"Count") {
-            pages_dict.set("Count", Object::Integer(count + 1));
+/// Consecutive content of one page of a paginated table
+#[derive(Debug)]
+pub(crate) enum PageSection {
+    /// Rows of the table itself
+    Rows(Vec<PageRow>),
+    /// Rows generated while paginating, if any
+    Generated(Option<Box<GeneratedRow>>),
+}
+
+impl PageSection {
+    pub(crate) fn height(&self) -> f32 {
+        match self {
+            Self::Rows(rows) => rows.iter().map(|r| r.height).sum(),
+            Self::Generated(generated) => generated.as_ref().map_or(0.0, |g| g.layout.total_height),
         }
     }
-
-    trace!("Created new page {:?}", new_page_id);
-    Ok(new_page_id)
 }
 
-/// Draw a subset of rows on a specific page
-fn draw_rows_subset(
+/// Draw the sections of one page stacked from `position`
+fn draw_page_sections(
     doc: &mut Document,
     page_id: ObjectId,
     table: &Table,
     layout: &TableLayout,
-    row_indices: &[usize],
+    sections: &[PageSection],
     position: (f32, f32),
     hook: &mut Option<&mut dyn TaggedCellHook>,
     image_registry: Option<&ImageXObjects>,
 ) -> Result<()> {
-    if row_indices.is_empty() {
-        return Ok(());
-    }
-
-    debug!("Drawing {} rows on page {:?}", row_indices.len(), page_id);
-
+    let (start_x, mut current_y) = position;
     let mut operations = Vec::new();
-    let mut cell_border_overlay_ops = Vec::new();
-    let (start_x, start_y) = position;
-    let mut current_y = start_y;
-    let artifactize_non_semantic = hook.is_some();
-
-    // Calculate which columns to draw (all columns for now)
-    let column_count = table.column_count();
-
-    // Draw table background if this is the first page
-    if row_indices.contains(&0) {
-        if let Some(bg_color) = &table.style.background_color {
-            let subset_height: f32 = row_indices.iter().map(|&i| layout.row_heights[i]).sum();
-            let bg_ops = draw_rectangle_fill(
-                start_x,
-                start_y - subset_height,
-                layout.total_width,
-                subset_height,
-                *bg_color,
-            );
-            if artifactize_non_semantic {
-                operations.extend(wrap_objects_as_artifact(bg_ops));
-            } else {
-                operations.extend(bg_ops);
-            }
-        }
-    }
-
-    // Draw rows
-    for &row_idx in row_indices {
-        let row = &table.rows[row_idx];
-        let row_height = layout.row_heights[row_idx];
-        let mut current_x = start_x;
-
-        // Draw row background if specified
-        if let Some(ref row_style) = row.style {
-            if let Some(bg_color) = row_style.background_color {
-                let row_bg_ops = draw_rectangle_fill(
-                    start_x,
-                    current_y - row_height,
-                    layout.total_width,
-                    row_height,
-                    bg_color,
-                );
-                if artifactize_non_semantic {
-                    operations.extend(wrap_objects_as_artifact(row_bg_ops));
-                } else {
-                    operations.extend(row_bg_ops);
-                }
-            }
-        }
-
-        // Draw cells
-        let mut col_idx = 0;
-        for cell in row.cells.iter() {
-            if col_idx >= column_count {
-                break;
-            }
-            let is_header = row_idx < table.header_rows;
-
-            if let Some(cell_hook) = hook.as_deref_mut() {
-                operations.extend(operations_to_objects(
-                    cell_hook.begin_cell(row_idx, col_idx, is_header),
-                ));
-            }
-
-            // Calculate the total width for cells with colspan
-            let cell_width = calculate_cell_width(col_idx, cell.colspan, &layout.column_widths);
-
-            // Draw cell background if specified
-            if let Some(ref cell_style) = cell.style {
-                if let Some(bg_color) = cell_style.background_color {
-                    operations.extend(draw_rectangle_fill(
-                        current_x,
-                        current_y - row_height,
-                        cell_width,
-                        row_height,
-                        bg_color,
-                    ));
-                }
-            }
-
-            // Draw cell content
-            operations.extend(draw_cell_text(
-                cell, table, current_x, current_y, cell_width, row_height,
-            )?);
-
-            // Draw cell images if present
-            if let Some(registry) = image_registry {
-                if !cell.images.is_empty() {
-                    let padding = cell
-                        .style
-                        .as_ref()
-                        .and_then(|s| s.padding.as_ref())
-                        .unwrap_or(&table.style.padding);
-                    operations.extend(draw_cell_images(
-                        &cell.images,
-                        registry,
-                        current_x,
-                        current_y,
-                        cell_width,
-                        row_height,
-                        padding,
-                        table,
-                    ));
-                }
-            }
 
-            if let Some(cell_hook) = hook.as_deref_mut() {
-                operations.extend(operations_to_objects(
-                    cell_hook.end_cell(row_idx, col_idx, is_header),
-                ));
+    for section in sections {
+        match section {
+            PageSection::Rows(rows) if !rows.is_empty() => {
+                debug!("Drawing {} rows on page {:?}", rows.len(), page_id);
+                operations.extend(generate_rows_operations(
+                    table,
+                    layout,
+                    rows,
+                    (start_x, current_y),
+                    hook,
+                    image_registry,
+                )?);
+                current_y -= rows.iter().map(|r| r.height).sum::<f32>();
             }
-
-            // Draw per-cell border overrides after semantic cell content so they remain visual-only.
-            if let Some(ref cell_style) = cell.style {
-                let cell_border_ops = draw_cell_border_overrides(
-                    cell_style, current_x, current_y, cell_width, row_height,
-                );
-                if artifactize_non_semantic {
-                    cell_border_overlay_ops.extend(wrap_objects_as_artifact(cell_border_ops));
+            PageSection::Generated(Some(generated)) => {
+                let rows: Vec<PageRow> = (0..generated.table.rows.len())
+                    .map(|row| PageRow::whole(row, &generated.layout))
+                    .collect();
+                let generated_ops = generate_rows_operations(
+                    &generated.table,
+                    &generated.layout,
+                    &rows,
+                    (start_x, current_y),
+                    &mut None,
+                    None,
+                )?;
+                // Generated rows are pagination artifacts, not table content
+                if hook.is_some() {
+                    operations.extend(wrap_objects_as_artifact(generated_ops));
                 } else {
-                    cell_border_overlay_ops.extend(cell_border_ops);
+                    operations.extend(generated_ops);
                 }
+                current_y -= generated.layout.total_height;
             }
-
-            current_x += cell_width;
-            col_idx += cell.colspan.max(1);
+            _ => {}
         }
-
-        current_y -= row_height;
     }
 
-    // Draw borders for this subset
-    let border_ops = draw_subset_borders(table, layout, row_indices, position);
-    if artifactize_non_semantic {
-        operations.extend(wrap_objects_as_artifact(border_ops));
-    } else {
-        operations.extend(border_ops);
-    }
-    operations.extend(cell_border_overlay_ops);
-
-    // Register image XObjects on this page
-    if let Some(registry) = image_registry {
-        registry.register_on_page(doc, page_id)?;
+    if operations.is_empty() {
+        return Ok(());
     }
 
     // Add operations to page
@@ -1288,23 +2189,3 @@ fn draw_rows_subset(
 
     Ok(())
 }
-
-/// Draw borders for a subset of rows (wrapper for the shared utility)
-fn draw_subset_borders(
-    table: &Table,
-    layout: &TableLayout,
-    row_indices: &[usize],
-    position: (f32, f32),
-) -> Vec<Object> {
-    if row_indices.is_empty() {
-        return Vec::new();
-    }
-    let subset_height: f32 = row_indices.iter().map(|&i| layout.row_heights[i]).sum();
-    draw_borders_util(
-        table,
-        layout,
-        position,
-        BorderDrawingMode::Subset(subset_height),
-        Some(row_indices),
-    )
-}
@@ -1,6 +1,6 @@
 //! Shared drawing utilities for PDF table operations
 
-use crate::constants::*;
+use crate::drawing::PageRow;
 use crate::layout::TableLayout;
 use crate::style::{BorderStyle, Color};
 use crate::table::Table;
@@ -110,12 +110,6 @@ pub fn calculate_cell_width(col_idx: usize, colspan: usize, column_widths: &[f32
     }
 }
 
-/// Estimate text width based on character count and font size
-pub fn estimate_text_width(text: &str, font_size: f32) -> f32 {
-    let char_count = text.chars().count() as f32;
-    char_count * font_size * DEFAULT_CHAR_WIDTH_RATIO
-}
-
 /// Measure text width using font metrics
 pub fn estimate_text_width_with_metrics(
     text: &str,
@@ -164,41 +158,41 @@ pub fn objects_to_operations(objects: &[Object]) -> Vec<Operation> {
     operations
 }
 
-/// Enum to specify border drawing mode
-#[derive(Debug, Clone, Copy, PartialEq)]
-pub enum BorderDrawingMode {
-    /// Draw borders for the entire table
-    Full,
-    /// Draw borders for a subset of rows
-    Subset(f32), // subset_height
-}
-
-/// Draw table borders (handles both full and subset modes)
+/// Draw table borders for an ordered list of page rows stacked from `position`.
+///
+/// Grid lines are omitted inside merged cells: no horizontal segment is drawn
+/// through a cell spanning consecutive rows, and no vertical segment through a
+/// cell spanning adjacent columns.
 pub fn draw_table_borders(
     table: &Table,
     layout: &TableLayout,
     position: (f32, f32),
-    mode: BorderDrawingMode,
-    row_indices: Option<&[usize]>,
+    rows: &[PageRow],
 ) -> Vec<Object> {
     let mut operations = Vec::new();
     let (start_x, start_y) = position;
 
-    if table.style.border_style == BorderStyle::None {
+    if table.style.border_style == BorderStyle::None || rows.is_empty() {
         return operations;
     }
 
+    let grid = &layout.grid;
+    let column_count = grid.columns().min(layout.column_widths.len());
+    let mut column_edges = Vec::with_capacity(column_count + 1);
+    let mut edge = start_x;
+    column_edges.push(edge);
+    for width in &layout.column_widths[..column_count] {
+        edge += width;
+        column_edges.push(edge);
+    }
+
     // Set stroke color and width
     operations.extend(set_stroke_style(
         table.style.border_color,
         table.style.border_width,
     ));
 
-    // Determine height based on mode
-    let total_height = match mode {
-        BorderDrawingMode::Full => layout.total_height,
-        BorderDrawingMode::Subset(height) => height,
-    };
+    let total_height: f32 = rows.iter().map(|r| r.height).sum();
 
     // Draw outer border
     operations.extend(draw_rectangle_stroke(
@@ -208,67 +202,46 @@ pub fn draw_table_borders(
         total_height,
     ));
 
-    // Draw horizontal lines between rows
-    let rows_to_process: Vec<usize> = match row_indices {
-        Some(indices) => indices.to_vec(),
-        None => (0..layout.row_heights.len()).collect(),
-    };
-
+    // Draw horizontal lines between rows, skipping cells that span both rows
     let mut current_y = start_y;
-    for (idx, &row_idx) in rows_to_process.iter().enumerate() {
+    for (idx, page_row) in rows.iter().enumerate() {
+        let row_idx = page_row.row;
         if idx > 0 {
-            operations.extend(draw_horizontal_line(
-                start_x,
-                start_x + layout.total_width,
-                current_y,
-            ));
-        }
-        if row_idx < layout.row_heights.len() {
-            current_y -= layout.row_heights[row_idx];
+            let prev_row = rows[idx - 1].row;
+            let contiguous = prev_row + 1 == row_idx;
+            let mut segment_start: Option<usize> = None;
+            for col in 0..=column_count {
+                let merged = col < column_count
+                    && contiguous
+                    && grid.slot(prev_row, col) == grid.slot(row_idx, col);
+                match (segment_start, col < column_count && !merged) {
+                    (None, true) => segment_start = Some(col),
+                    (Some(first), false) => {
+                        operations.extend(draw_horizontal_line(
+                            column_edges[first],
+                            column_edges[col],
+                            current_y,
+                        ));
+                        segment_start = None;
+                    }
+                    _ => {}
+                }
+            }
         }
+        current_y -= page_row.height;
     }
 
-    // Draw vertical lines between columns (handling colspan)
-    for (idx, &row_idx) in rows_to_process.iter().enumerate() {
-        if row_idx >= table.rows.len() {
-            continue;
-        }
-
-        let row = &table.rows[row_idx];
-        let mut current_x = start_x;
-        let mut col_idx = 0;
-
-        let row_y_top = if let Some(indices) = row_indices {
-            start_y
-                - indices
-                    .iter()
-                    .take(idx)
-                    .map(|&i| layout.row_heights[i])
-                    .sum::<f32>()
-        } else {
-            start_y - layout.row_heights.iter().take(row_idx).sum::<f32>()
-        };
-        let row_y_bottom = row_y_top - layout.row_heights[row_idx];
-
-        for cell in &row.cells {
-            if col_idx >= layout.column_widths.len() {
-                break;
-            }
-
-            // Draw vertical line at the start of this cell (if not first column)
-            if col_idx > 0 {
-                operations.extend(draw_vertical_line(current_x, row_y_top, row_y_bottom));
-            }
-
-            // Move across the span of this cell
-            let cell_span = cell.colspan.max(1);
-            for span_idx in 0..cell_span {
-                if col_idx + span_idx < layout.column_widths.len() {
-                    current_x += layout.column_widths[col_idx + span_idx];
-                }
+    // Draw vertical lines between columns, skipping cells that span both columns
+    let mut row_y_top = start_y;
+    for page_row in rows {
+        let row_idx = page_row.row;
+        let row_y_bottom = row_y_top - page_row.height;
+        for (col, &x) in column_edges.iter().enumerate().take(column_count).skip(1) {
+            if grid.slot(row_idx, col - 1) != grid.slot(row_idx, col) {
+                operations.extend(draw_vertical_line(x, row_y_top, row_y_bottom));
             }
-            col_idx += cell_span;
         }
+        row_y_top = row_y_bottom;
     }
 
     operations
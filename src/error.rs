@@ -36,6 +36,11 @@ pub enum TableError {
     #[error("Page with ID {0:?} not found")]
     PageNotFound(lopdf::ObjectId),
 
+    /// Tables split into column bands are planned band by band while drawing,
+    /// so they have no single plan
+    #[error("Tables split into column bands cannot be planned")]
+    BandedTablePlan,
+
     /// Drawing operation error
     #[error("Drawing operation failed: {0}")]
     DrawingError(String),
@@ -2,6 +2,10 @@
 
 #[cfg(feature = "ttf-parser")]
 use crate::constants::DEFAULT_CHAR_WIDTH_RATIO;
+#[cfg(feature = "ttf-parser")]
+use std::collections::HashMap;
+#[cfg(feature = "ttf-parser")]
+use std::sync::RwLock;
 
 /// Trait for measuring text dimensions and encoding text for PDF rendering.
 ///
@@ -20,59 +24,175 @@ pub trait FontMetrics: Send + Sync {
 
 /// TrueType font metrics using ttf-parser for accurate glyph measurement and encoding.
 ///
-/// This struct owns the font data and parses it on demand for measurements.
-/// The caller is responsible for embedding the font into the PDF document;
-/// this type only handles measurement and glyph ID encoding.
+/// Glyph IDs and advances are looked up in the font the first time a
+/// character is measured or encoded and remembered, so text is measured
+/// without parsing the font again and fonts with large character maps cost
+/// nothing up front. The caller is responsible for embedding the font into
+/// the PDF document; this type only handles measurement and glyph ID encoding.
 #[cfg(feature = "ttf-parser")]
 pub struct TtfFontMetrics {
     font_data: Vec<u8>,
     units_per_em: f32,
+    glyph_count: u16,
+    /// Glyph ID of every character looked up so far, `None` when unmapped
+    glyph_ids: RwLock<HashMap<char, Option<u16>>>,
+    /// Horizontal advance in font units of every glyph looked up so far
+    advances: RwLock<HashMap<u16, Option<u16>>>,
 }
 
 #[cfg(feature = "ttf-parser")]
 impl TtfFontMetrics {
     /// Create new font metrics from raw TTF/TTC font data.
     ///
-    /// Validates the font by parsing it and extracting units_per_em.
+    /// Validates the font by parsing it.
     pub fn new(font_data: Vec<u8>) -> crate::Result<Self> {
         let face = ttf_parser::Face::parse(&font_data, 0).map_err(|e| {
             crate::error::TableError::TextError(format!("Failed to parse font: {e}"))
         })?;
         let units_per_em = face.units_per_em() as f32;
+        let glyph_count = face.number_of_glyphs();
+
         Ok(Self {
             font_data,
             units_per_em,
+            glyph_count,
+            glyph_ids: RwLock::default(),
+            advances: RwLock::default(),
         })
     }
+
+    /// Parse the font again, which `new` has already validated
+    fn face(&self) -> Option<ttf_parser::Face<'_>> {
+        ttf_parser::Face::parse(&self.font_data, 0).ok()
+    }
+
+    /// Look up the glyph IDs and advances of the characters of `text` not
+    /// looked up yet, parsing the font once for all of them
+    fn cache_chars(&self, text: &str) {
+        let missing: Vec<char> = {
+            let glyph_ids = read(&self.glyph_ids);
+            text.chars()
+                .filter(|ch| !glyph_ids.contains_key(ch))
+                .collect()
+        };
+        if missing.is_empty() {
+            return;
+        }
+        let Some(face) = self.face() else {
+            return;
+        };
+        let mut glyph_ids = write(&self.glyph_ids);
+        let mut advances = write(&self.advances);
+        for ch in missing {
+            let gid = face.glyph_index(ch);
+            glyph_ids.insert(ch, gid.map(|g| g.0));
+            if let Some(gid) = gid {
+                advances
+                    .entry(gid.0)
+                    .or_insert_with(|| face.glyph_hor_advance(gid));
+            }
+        }
+    }
+
+    /// Glyph ID of a character, if the font maps it
+    pub fn glyph_id(&self, ch: char) -> Option<u16> {
+        if let Some(&gid) = read(&self.glyph_ids).get(&ch) {
+            return gid;
+        }
+        self.cache_chars(ch.encode_utf8(&mut [0; 4]));
+        read(&self.glyph_ids).get(&ch).copied().flatten()
+    }
+
+    /// Horizontal advance of a glyph in font units
+    pub(crate) fn advance(&self, gid: u16) -> Option<u16> {
+        if let Some(&advance) = read(&self.advances).get(&gid) {
+            return advance;
+        }
+        let advance = self
+            .face()
+            .and_then(|face| face.glyph_hor_advance(ttf_parser::GlyphId(gid)));
+        write(&self.advances).insert(gid, advance);
+        advance
+    }
+
+    /// Raw TTF/TTC font data
+    pub(crate) fn font_data(&self) -> &[u8] {
+        &self.font_data
+    }
+
+    pub(crate) fn units_per_em(&self) -> f32 {
+        self.units_per_em
+    }
+
+    /// Number of glyphs in the font
+    pub(crate) fn glyph_count(&self) -> usize {
+        self.glyph_count as usize
+    }
+
+    /// Every character mapped by the font's Unicode cmap subtables, with its
+    /// glyph ID. The first subtable mapping a character wins, as in
+    /// `Face::glyph_index`.
+    pub(crate) fn mapped_chars(&self) -> Vec<(char, u16)> {
+        let Some(face) = self.face() else {
+            return Vec::new();
+        };
+        let mut glyph_ids = HashMap::new();
+        for subtable in face
+            .tables()
+            .cmap
+            .into_iter()
+            .flat_map(|cmap| cmap.subtables)
+        {
+            if !subtable.is_unicode() {
+                continue;
+            }
+            subtable.codepoints(|code_point| {
+                let Some(ch) = char::from_u32(code_point) else {
+                    return;
+                };
+                if glyph_ids.contains_key(&ch) {
+                    return;
+                }
+                if let Some(gid) = subtable.glyph_index(code_point) {
+                    glyph_ids.insert(ch, gid.0);
+                }
+            });
+        }
+        glyph_ids.into_iter().collect()
+    }
+}
+
+/// Read a lookup cache, which stays consistent even if a thread panicked
+/// while filling it
+#[cfg(feature = "ttf-parser")]
+fn read<T>(lock: &RwLock<T>) -> std::sync::RwLockReadGuard<'_, T> {
+    lock.read().unwrap_or_else(|e| e.into_inner())
+}
+
+#[cfg(feature = "ttf-parser")]
+fn write<T>(lock: &RwLock<T>) -> std::sync::RwLockWriteGuard<'_, T> {
+    lock.write().unwrap_or_else(|e| e.into_inner())
 }
 
 #[cfg(feature = "ttf-parser")]
 impl FontMetrics for TtfFontMetrics {
     fn char_width(&self, ch: char, font_size: f32) -> f32 {
-        let face = ttf_parser::Face::parse(&self.font_data, 0).unwrap();
-        face.glyph_index(ch)
-            .and_then(|gid| face.glyph_hor_advance(gid))
+        self.glyph_id(ch)
+            .and_then(|gid| self.advance(gid))
             .map(|advance| advance as f32 / self.units_per_em * font_size)
             .unwrap_or(font_size * DEFAULT_CHAR_WIDTH_RATIO)
     }
 
     fn text_width(&self, text: &str, font_size: f32) -> f32 {
-        let face = ttf_parser::Face::parse(&self.font_data, 0).unwrap();
-        text.chars()
-            .map(|ch| {
-                face.glyph_index(ch)
-                    .and_then(|gid| face.glyph_hor_advance(gid))
-                    .map(|advance| advance as f32 / self.units_per_em * font_size)
-                    .unwrap_or(font_size * DEFAULT_CHAR_WIDTH_RATIO)
-            })
-            .sum()
+        self.cache_chars(text);
+        text.chars().map(|ch| self.char_width(ch, font_size)).sum()
     }
 
     fn encode_text(&self, text: &str) -> Vec<u8> {
-        let face = ttf_parser::Face::parse(&self.font_data, 0).unwrap();
+        self.cache_chars(text);
         let mut bytes = Vec::with_capacity(text.len() * 2);
         for ch in text.chars() {
-            let glyph_id = face.glyph_index(ch).map(|g| g.0).unwrap_or(0);
+            let glyph_id = self.glyph_id(ch).unwrap_or(0);
             bytes.extend_from_slice(&glyph_id.to_be_bytes());
         }
         bytes
@@ -85,6 +205,7 @@ impl std::fmt::Debug for TtfFontMetrics {
         f.debug_struct("TtfFontMetrics")
             .field("units_per_em", &self.units_per_em)
             .field("font_data_len", &self.font_data.len())
+            .field("glyph_count", &self.glyph_count)
             .finish()
     }
 }
@@ -173,4 +294,40 @@ mod tests {
         let encoded = metrics.encode_text("café");
         assert_eq!(encoded.len(), 8, "4 chars should produce 8 bytes");
     }
+
+    #[test]
+    fn test_cached_lookups_match_parsed_face() {
+        let Some(font_data) = crate::test_support::dejavu_sans() else {
+            return;
+        };
+        let face = ttf_parser::Face::parse(&font_data, 0).unwrap();
+        let units_per_em = face.units_per_em() as f32;
+        let metrics = TtfFontMetrics::new(font_data.clone()).unwrap();
+
+        for ch in ['A', 'g', ' ', 'é', '€', 'Ω', '\u{10FFFF}'] {
+            let gid = face.glyph_index(ch);
+            assert_eq!(metrics.glyph_id(ch), gid.map(|g| g.0), "glyph of {ch:?}");
+            let expected = gid
+                .and_then(|g| face.glyph_hor_advance(g))
+                .map(|advance| advance as f32 / units_per_em * 12.0)
+                .unwrap_or(12.0 * DEFAULT_CHAR_WIDTH_RATIO);
+            assert_eq!(metrics.char_width(ch, 12.0), expected, "width of {ch:?}");
+        }
+    }
+
+    #[test]
+    fn test_lookups_are_made_once_per_character() {
+        let Some(font_data) = crate::test_support::dejavu_sans() else {
+            return;
+        };
+        let metrics = TtfFontMetrics::new(font_data).unwrap();
+        assert!(read(&metrics.glyph_ids).is_empty());
+
+        let width = metrics.text_width("Hello", 12.0);
+        assert_eq!(read(&metrics.glyph_ids).len(), 4);
+        assert_eq!(read(&metrics.advances).len(), 4);
+        assert_eq!(metrics.text_width("Hello", 12.0), width);
+        assert_eq!(read(&metrics.glyph_ids).len(), 4);
+        assert!(metrics.mapped_chars().len() > 100);
+    }
 }
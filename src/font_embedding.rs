@@ -0,0 +1,568 @@
+//! Embedding TrueType fonts as Type0/CIDFontType2 fonts
+
+use crate::Result;
+use crate::error::TableError;
+use crate::font::{FontMetrics, TtfFontMetrics};
+use crate::font_subset::subset_truetype;
+use lopdf::{Dictionary, Document, Object, ObjectId, Stream, dictionary};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Maximum number of entries in one `beginbfchar` block of a CMap
+const BFCHAR_BLOCK_SIZE: usize = 100;
+
+/// A TrueType font embedded in a document, ready to be used by tables.
+///
+/// The font is embedded as a Type0 font with Identity-H encoding over a
+/// CIDFontType2 font, with a `W` widths array and a ToUnicode CMap so that the
+/// text can be extracted and copied. Pass it to
+/// [`Table::with_embedded_font`](crate::Table::with_embedded_font) to measure
+/// and encode text with its metrics and draw it with this font.
+///
+/// Fonts embedded with [`subset`](Self::subset) record the glyphs of the text
+/// they encode, across every table drawn with them (clones share the record),
+/// and only embed those glyphs. The subset is rewritten whenever a table is
+/// drawn with the font.
+#[derive(Debug, Clone)]
+pub struct EmbeddedFont {
+    metrics: Arc<TtfFontMetrics>,
+    resource_name: String,
+    objects: FontObjects,
+    subset: bool,
+    /// Glyphs encoded so far, with the character each was first encoded from
+    used_glyphs: Arc<Mutex<BTreeMap<u16, char>>>,
+    /// Number of glyphs in the subset last written
+    written_glyphs: Arc<AtomicUsize>,
+}
+
+/// Objects making up an embedded font, kept so the font can be rewritten in
+/// place
+#[derive(Debug, Clone, Copy)]
+struct FontObjects {
+    font: ObjectId,
+    cid_font: ObjectId,
+    descriptor: ObjectId,
+    font_file: ObjectId,
+    to_unicode: ObjectId,
+}
+
+impl EmbeddedFont {
+    /// Embed the whole font of `metrics` in the document, to be referenced
+    /// from page resources as `resource_name` (e.g. "EF0")
+    pub fn embed(
+        doc: &mut Document,
+        metrics: TtfFontMetrics,
+        resource_name: impl Into<String>,
+    ) -> Result<Self> {
+        let font = Self::new(doc, metrics, resource_name.into(), false);
+        font.write(doc)?;
+        Ok(font)
+    }
+
+    /// Parse raw TTF/TTC font data and embed the whole font in the document
+    pub fn from_bytes(
+        doc: &mut Document,
+        font_data: Vec<u8>,
+        resource_name: impl Into<String>,
+    ) -> Result<Self> {
+        Self::embed(doc, TtfFontMetrics::new(font_data)?, resource_name)
+    }
+
+    /// Embed a subset of the font of `metrics` in the document, holding only
+    /// the glyphs of the text drawn with it.
+    ///
+    /// Tables drawn with the font rewrite the subset once they are drawn. Call
+    /// [`write_subset`](Self::write_subset) after encoding text with it any
+    /// other way (e.g. for [`create_table_content`](crate::TableDrawing::create_table_content)).
+    /// Only fonts with TrueType outlines can be subset.
+    pub fn subset(
+        doc: &mut Document,
+        metrics: TtfFontMetrics,
+        resource_name: impl Into<String>,
+    ) -> Result<Self> {
+        let font = Self::new(doc, metrics, resource_name.into(), true);
+        font.write(doc)?;
+        Ok(font)
+    }
+
+    fn new(
+        doc: &mut Document,
+        metrics: TtfFontMetrics,
+        resource_name: String,
+        subset: bool,
+    ) -> Self {
+        let objects = FontObjects {
+            font: doc.new_object_id(),
+            cid_font: doc.new_object_id(),
+            descriptor: doc.new_object_id(),
+            font_file: doc.new_object_id(),
+            to_unicode: doc.new_object_id(),
+        };
+        Self {
+            metrics: Arc::new(metrics),
+            resource_name,
+            objects,
+            subset,
+            used_glyphs: Arc::default(),
+            written_glyphs: Arc::default(),
+        }
+    }
+
+    /// Rewrite a subset font with the glyphs of all text encoded so far.
+    ///
+    /// Does nothing for fonts embedded whole, or when no glyph was added since
+    /// the subset was last written.
+    pub fn write_subset(&self, doc: &mut Document) -> Result<()> {
+        let glyph_count = self.lock_used_glyphs().len();
+        if !self.subset || self.written_glyphs.load(Ordering::Relaxed) == glyph_count {
+            return Ok(());
+        }
+        self.write(doc)?;
+        self.written_glyphs.store(glyph_count, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// Name of the font in page resources
+    pub fn resource_name(&self) -> &str {
+        &self.resource_name
+    }
+
+    /// Object ID of the Type0 font dictionary
+    pub fn font_id(&self) -> ObjectId {
+        self.objects.font
+    }
+
+    /// Metrics used to measure and encode text drawn with the font
+    pub fn metrics(&self) -> &Arc<TtfFontMetrics> {
+        &self.metrics
+    }
+
+    /// IDs of the glyphs encoded so far, in ascending order
+    pub fn used_glyphs(&self) -> Vec<u16> {
+        self.lock_used_glyphs().keys().copied().collect()
+    }
+
+    fn lock_used_glyphs(&self) -> std::sync::MutexGuard<'_, BTreeMap<u16, char>> {
+        // The record stays consistent even if a thread panicked while encoding
+        self.used_glyphs.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Write the Type0 font, its descendant CIDFontType2 font, descriptor,
+    /// font program and ToUnicode CMap to the document
+    fn write(&self, doc: &mut Document) -> Result<()> {
+        let metrics = &self.metrics;
+        let face = ttf_parser::Face::parse(metrics.font_data(), 0)
+            .map_err(|e| TableError::TextError(format!("Failed to parse font: {e}")))?;
+        let scale = 1000.0 / metrics.units_per_em();
+        let to_pdf_units = |value: i16| (value as f32 * scale).round() as i64;
+        let glyph_width = |gid: u16| {
+            let advance = metrics.advance(gid).unwrap_or(0);
+            (gid, (advance as f32 * scale).round() as i64)
+        };
+
+        let (base_font, program, widths, glyph_chars) = if self.subset {
+            let glyph_chars = self.lock_used_glyphs().clone();
+            let mut glyphs: BTreeSet<u16> = glyph_chars.keys().copied().collect();
+            glyphs.insert(0);
+            let program = subset_truetype(metrics.font_data(), &glyphs)?;
+            let base_font = format!("{}+{}", subset_tag(&glyphs), postscript_name(&face));
+            let widths = widths_array(glyphs.iter().map(|&gid| glyph_width(gid)));
+            (base_font, program, widths, glyph_chars)
+        } else {
+            let widths = widths_array((0..metrics.glyph_count() as u16).map(glyph_width));
+            (
+                postscript_name(&face),
+                metrics.font_data().to_vec(),
+                widths,
+                mapped_glyph_chars(metrics),
+            )
+        };
+        let base_font = Object::Name(base_font.into_bytes());
+
+        let mut font_file = Stream::new(dictionary! { "Length1" => program.len() as i64 }, program);
+        let _ = font_file.compress();
+        doc.set_object(self.objects.font_file, font_file);
+
+        let bbox = face.global_bounding_box();
+        let mut flags = 32; // Nonsymbolic
+        if face.is_monospaced() {
+            flags |= 1;
+        }
+        if face.is_italic() {
+            flags |= 64;
+        }
+        doc.set_object(
+            self.objects.descriptor,
+            dictionary! {
+                "Type" => "FontDescriptor",
+                "FontName" => base_font.clone(),
+                "Flags" => flags,
+                "FontBBox" => vec![
+                    to_pdf_units(bbox.x_min).into(),
+                    to_pdf_units(bbox.y_min).into(),
+                    to_pdf_units(bbox.x_max).into(),
+                    to_pdf_units(bbox.y_max).into(),
+                ],
+                "ItalicAngle" => face.italic_angle(),
+                "Ascent" => to_pdf_units(face.ascender()),
+                "Descent" => to_pdf_units(face.descender()),
+                "CapHeight" => to_pdf_units(face.capital_height().unwrap_or(face.ascender())),
+                "StemV" => 80,
+                "FontFile2" => self.objects.font_file,
+            },
+        );
+
+        doc.set_object(
+            self.objects.cid_font,
+            dictionary! {
+                "Type" => "Font",
+                "Subtype" => "CIDFontType2",
+                "BaseFont" => base_font.clone(),
+                "CIDSystemInfo" => dictionary! {
+                    "Registry" => Object::string_literal("Adobe"),
+                    "Ordering" => Object::string_literal("Identity"),
+                    "Supplement" => 0,
+                },
+                "FontDescriptor" => self.objects.descriptor,
+                "DW" => 1000,
+                "W" => widths,
+                "CIDToGIDMap" => "Identity",
+            },
+        );
+
+        let mut to_unicode = Stream::new(
+            Dictionary::new(),
+            to_unicode_cmap_for(&glyph_chars).into_bytes(),
+        );
+        let _ = to_unicode.compress();
+        doc.set_object(self.objects.to_unicode, to_unicode);
+
+        doc.set_object(
+            self.objects.font,
+            dictionary! {
+                "Type" => "Font",
+                "Subtype" => "Type0",
+                "BaseFont" => base_font,
+                "Encoding" => "Identity-H",
+                "DescendantFonts" => vec![self.objects.cid_font.into()],
+                "ToUnicode" => self.objects.to_unicode,
+            },
+        );
+        Ok(())
+    }
+}
+
+impl FontMetrics for EmbeddedFont {
+    fn char_width(&self, ch: char, font_size: f32) -> f32 {
+        self.metrics.char_width(ch, font_size)
+    }
+
+    fn text_width(&self, text: &str, font_size: f32) -> f32 {
+        self.metrics.text_width(text, font_size)
+    }
+
+    fn encode_text(&self, text: &str) -> Vec<u8> {
+        let mut used_glyphs = self.lock_used_glyphs();
+        for ch in text.chars() {
+            if let Some(gid) = self.metrics.glyph_id(ch) {
+                used_glyphs.entry(gid).or_insert(ch);
+            }
+        }
+        drop(used_glyphs);
+        self.metrics.encode_text(text)
+    }
+}
+
+/// Tag of six uppercase letters prefixed to the name of a subset font,
+/// derived from its glyphs so the same subset always gets the same tag
+fn subset_tag(glyphs: &BTreeSet<u16>) -> String {
+    // FNV-1a
+    let mut hash: u32 = 0x811C_9DC5;
+    for gid in glyphs {
+        for byte in gid.to_be_bytes() {
+            hash = (hash ^ byte as u32).wrapping_mul(0x0100_0193);
+        }
+    }
+    (0..6)
+        .map(|_| {
+            let letter = (b'A' + (hash % 26) as u8) as char;
+            hash /= 26;
+            letter
+        })
+        .collect()
+}
+
+/// PostScript name of the font, usable as a PDF name
+fn postscript_name(face: &ttf_parser::Face) -> String {
+    face.names()
+        .into_iter()
+        .filter(|name| name.name_id == ttf_parser::name_id::POST_SCRIPT_NAME)
+        .find_map(|name| name.to_string())
+        .map(|name| {
+            name.chars()
+                .filter(|ch| ch.is_ascii_graphic() && !"()<>[]{}/%#".contains(*ch))
+                .collect::<String>()
+        })
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| "EmbeddedFont".to_string())
+}
+
+/// `W` array of a CIDFont: runs of consecutive glyph IDs, each followed by
+/// the array of their widths
+fn widths_array(widths: impl IntoIterator<Item = (u16, i64)>) -> Vec<Object> {
+    let mut array = Vec::new();
+    let mut run: Vec<Object> = Vec::new();
+    let mut next_gid = None;
+
+    for (gid, width) in widths {
+        if next_gid != Some(gid) && !run.is_empty() {
+            array.push(Object::Array(std::mem::take(&mut run)));
+        }
+        if run.is_empty() {
+            array.push((gid as i64).into());
+        }
+        run.push(width.into());
+        next_gid = gid.checked_add(1);
+    }
+    if !run.is_empty() {
+        array.push(Object::Array(run));
+    }
+    array
+}
+
+/// Every glyph of the font mapped from a character, with the lowest such
+/// character
+fn mapped_glyph_chars(metrics: &TtfFontMetrics) -> BTreeMap<u16, char> {
+    let mut glyph_chars = BTreeMap::new();
+    for (ch, gid) in metrics.mapped_chars() {
+        glyph_chars
+            .entry(gid)
+            .and_modify(|mapped: &mut char| *mapped = (*mapped).min(ch))
+            .or_insert(ch);
+    }
+    glyph_chars
+}
+
+/// ToUnicode CMap for 2-byte glyph ID codes
+fn to_unicode_cmap_for(glyph_chars: &BTreeMap<u16, char>) -> String {
+    let mut cmap = String::from(
+        "/CIDInit /ProcSet findresource begin\n\
+         12 dict begin\n\
+         begincmap\n\
+         /CIDSystemInfo << /Registry (Adobe) /Ordering (UCS) /Supplement 0 >> def\n\
+         /CMapName /Adobe-Identity-UCS def\n\
+         /CMapType 2 def\n\
+         1 begincodespacerange\n\
+         <0000> <FFFF>\n\
+         endcodespacerange\n",
+    );
+
+    let entries: Vec<_> = glyph_chars.iter().filter(|(gid, _)| **gid != 0).collect();
+    for block in entries.chunks(BFCHAR_BLOCK_SIZE) {
+        let _ = writeln!(cmap, "{} beginbfchar", block.len());
+        for (gid, ch) in block {
+            let mut units = [0u16; 2];
+            let utf16: String = ch
+                .encode_utf16(&mut units)
+                .iter()
+                .map(|unit| format!("{unit:04X}"))
+                .collect();
+            let _ = writeln!(cmap, "<{gid:04X}> <{utf16}>");
+        }
+        cmap.push_str("endbfchar\n");
+    }
+
+    cmap.push_str(
+        "endcmap\n\
+         CMapName currentdict /CMap defineresource pop\n\
+         end\n\
+         end\n",
+    );
+    cmap
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TableDrawing;
+    use crate::font::TtfFontMetrics;
+    use crate::style::TableStyle;
+    use crate::table::{Cell, Row, Table};
+    use crate::test_support::*;
+
+    #[test]
+    fn test_widths_array_groups_consecutive_glyphs() {
+        let array = widths_array([(3, 500), (4, 600), (10, 250)]);
+        assert_eq!(
+            array,
+            vec![
+                Object::Integer(3),
+                Object::Array(vec![500.into(), 600.into()]),
+                Object::Integer(10),
+                Object::Array(vec![250.into()]),
+            ]
+        );
+        assert!(widths_array([]).is_empty());
+    }
+
+    #[test]
+    fn test_subset_tag_depends_on_glyphs_only() {
+        let glyphs = BTreeSet::from([0, 36, 68]);
+        let tag = subset_tag(&glyphs);
+        assert_eq!(tag.len(), 6);
+        assert!(tag.chars().all(|c| c.is_ascii_uppercase()));
+        assert_eq!(subset_tag(&glyphs.clone()), tag);
+        assert_ne!(subset_tag(&BTreeSet::from([0, 36])), tag);
+    }
+
+    #[test]
+    fn test_to_unicode_cmap_maps_glyphs_to_utf16() {
+        let glyph_chars = BTreeMap::from([(0, '?'), (36, 'A'), (900, '€'), (1200, '😀')]);
+        let cmap = to_unicode_cmap_for(&glyph_chars);
+
+        assert!(cmap.contains("3 beginbfchar\n"));
+        assert!(cmap.contains("<0024> <0041>\n"));
+        assert!(cmap.contains("<0384> <20AC>\n"));
+        assert!(cmap.contains("<04B0> <D83DDE00>\n"));
+        assert!(!cmap.contains("<0000> <003F>"));
+    }
+
+    #[cfg(feature = "ttf-parser")]
+    #[test]
+    fn test_embedded_font_added_to_every_drawn_page() {
+        let Some(font_data) = dejavu_sans() else {
+            return;
+        };
+        let (mut doc, page_id) = make_test_doc();
+        let font = EmbeddedFont::from_bytes(&mut doc, font_data, "EF0").unwrap();
+        let table = numbered_rows_table(40)
+            .add_row(Row::new(vec![Cell::new("Grüße €")]))
+            .with_embedded_font(&font);
+        assert_eq!(
+            table.style.embedded_font_resource_name.as_deref(),
+            Some("EF0")
+        );
+
+        let result = doc
+            .draw_table_with_pagination(page_id, table, (50.0, 792.0))
+            .unwrap();
+        assert_eq!(result.total_pages, 2);
+
+        for &page in &result.page_ids {
+            let fonts = doc.get_page_fonts(page).unwrap();
+            assert!(fonts.contains_key(b"F1".as_slice()));
+            let type0 = fonts[b"EF0".as_slice()];
+            assert_eq!(type0.get(b"Subtype").unwrap().as_name().unwrap(), b"Type0");
+            assert!(type0.has(b"ToUnicode"));
+        }
+
+        let type0 = doc.get_dictionary(font.font_id()).unwrap();
+        let descendant_id = type0.get(b"DescendantFonts").unwrap().as_array().unwrap()[0]
+            .as_reference()
+            .unwrap();
+        let cid_font = doc.get_dictionary(descendant_id).unwrap();
+        let widths = cid_font.get(b"W").unwrap().as_array().unwrap();
+        let gid = font.metrics().glyph_id('A').unwrap();
+        let advance = font.metrics().char_width('A', 1000.0).round() as i64;
+        assert_eq!(widths[0].as_i64().unwrap(), 0);
+        assert_eq!(
+            widths[1].as_array().unwrap()[gid as usize]
+                .as_i64()
+                .unwrap(),
+            advance
+        );
+
+        let last_page = doc.get_pages().len() as u32;
+        let text = doc.extract_text(&[last_page]).unwrap();
+        assert!(text.contains("Grüße €"), "extracted {text:?}");
+    }
+
+    #[cfg(feature = "ttf-parser")]
+    #[test]
+    fn test_embedded_font_kept_by_later_style() {
+        let Some(font_data) = dejavu_sans() else {
+            return;
+        };
+        let (mut doc, page_id) = make_test_doc();
+        let font = EmbeddedFont::from_bytes(&mut doc, font_data, "EF0").unwrap();
+        let table = Table::new()
+            .add_row(Row::new(vec![Cell::new("Grüße €")]))
+            .with_embedded_font(&font)
+            .with_style(TableStyle {
+                padding: crate::style::Padding::uniform(4.0),
+                ..Default::default()
+            });
+        assert_eq!(
+            table.style.embedded_font_resource_name.as_deref(),
+            Some("EF0")
+        );
+
+        doc.draw_table(page_id, table, (50.0, 792.0)).unwrap();
+        let text = doc.extract_text(&[1]).unwrap();
+        assert!(text.contains("Grüße €"), "extracted {text:?}");
+    }
+
+    #[cfg(feature = "ttf-parser")]
+    #[test]
+    fn test_subset_font_embeds_glyphs_of_every_table() {
+        let Some(font_data) = dejavu_sans() else {
+            return;
+        };
+        let full_len = font_data.len();
+        let (mut doc, page_id) = make_test_doc();
+        let metrics = TtfFontMetrics::new(font_data).unwrap();
+        let font = EmbeddedFont::subset(&mut doc, metrics, "EF0").unwrap();
+
+        for (text, y) in [("abc", 700.0), ("Ωé", 500.0)] {
+            let table = Table::new()
+                .add_row(Row::new(vec![Cell::new(text)]))
+                .with_embedded_font(&font);
+            doc.draw_table(page_id, table, (50.0, y)).unwrap();
+        }
+        // Drawing the tables rewrote the subset without calling write_subset
+
+        let glyph = |ch| font.metrics().glyph_id(ch).unwrap();
+        let mut expected: Vec<u16> = "abcΩé".chars().map(glyph).collect();
+        expected.sort();
+        assert_eq!(font.used_glyphs(), expected);
+
+        let type0 = doc.get_dictionary(font.font_id()).unwrap();
+        let base_font = type0.get(b"BaseFont").unwrap().as_name().unwrap();
+        assert_eq!(base_font[6], b'+');
+        assert!(base_font[..6].iter().all(u8::is_ascii_uppercase));
+        assert!(base_font.ends_with(b"DejaVuSans"));
+
+        let cid_font_id = type0.get(b"DescendantFonts").unwrap().as_array().unwrap()[0]
+            .as_reference()
+            .unwrap();
+        let cid_font = doc.get_dictionary(cid_font_id).unwrap();
+        let descriptor_id = cid_font
+            .get(b"FontDescriptor")
+            .unwrap()
+            .as_reference()
+            .unwrap();
+        let descriptor = doc.get_dictionary(descriptor_id).unwrap();
+        let font_file_id = descriptor
+            .get(b"FontFile2")
+            .unwrap()
+            .as_reference()
+            .unwrap();
+        let font_file = doc.get_object(font_file_id).unwrap().as_stream().unwrap();
+        let program = font_file.decompressed_content().unwrap();
+        assert!(program.len() < full_len / 4);
+        let subset = TtfFontMetrics::new(program).unwrap();
+        // The subset has no cmap, but keeps glyph IDs and their advances
+        assert_eq!(
+            subset.advance(glyph('Ω')),
+            font.metrics().advance(glyph('Ω'))
+        );
+
+        let text = doc.extract_text(&[1]).unwrap();
+        assert!(
+            text.contains("abc") && text.contains("Ωé"),
+            "extracted {text:?}"
+        );
+    }
+}
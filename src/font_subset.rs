@@ -0,0 +1,257 @@
+//! Subsetting of TrueType font programs for embedding
+
+use crate::Result;
+use crate::error::TableError;
+use std::collections::BTreeSet;
+
+/// Tables kept in a subset: those a PDF reader needs to render a
+/// CIDFontType2 font program (`cmap` is not needed with an identity
+/// CIDToGIDMap)
+const KEPT_TABLES: [&[u8; 4]; 9] = [
+    b"cvt ", b"fpgm", b"glyf", b"head", b"hhea", b"hmtx", b"loca", b"maxp", b"prep",
+];
+
+// Composite glyph component flags
+const ARG_1_AND_2_ARE_WORDS: u16 = 0x0001;
+const WE_HAVE_A_SCALE: u16 = 0x0008;
+const MORE_COMPONENTS: u16 = 0x0020;
+const WE_HAVE_AN_X_AND_Y_SCALE: u16 = 0x0040;
+const WE_HAVE_A_TWO_BY_TWO: u16 = 0x0080;
+
+/// Build a TrueType font program keeping only the outlines of `glyphs`.
+///
+/// Glyph IDs stay the same: unused glyphs keep an empty outline, and glyphs
+/// after the highest used one are dropped along with their metrics. Glyphs
+/// used as components of composite glyphs, and the `.notdef` glyph, are
+/// always kept.
+pub(crate) fn subset_truetype(font_data: &[u8], glyphs: &BTreeSet<u16>) -> Result<Vec<u8>> {
+    let face = ttf_parser::RawFace::parse(font_data, 0)
+        .map_err(|e| TableError::TextError(format!("Failed to parse font: {e}")))?;
+    let table = |tag: &[u8; 4]| face.table(ttf_parser::Tag::from_bytes(tag));
+    let missing = |tag: &str| TableError::TextError(format!("Font has no {tag} table to subset"));
+
+    let head = table(b"head").ok_or_else(|| missing("head"))?;
+    let maxp = table(b"maxp").ok_or_else(|| missing("maxp"))?;
+    let hhea = table(b"hhea").ok_or_else(|| missing("hhea"))?;
+    let hmtx = table(b"hmtx").ok_or_else(|| missing("hmtx"))?;
+    let loca = table(b"loca").ok_or_else(|| missing("loca"))?;
+    let glyf = table(b"glyf").ok_or_else(|| missing("glyf (TrueType outlines)"))?;
+    let malformed = || TableError::TextError("Malformed font table".to_string());
+
+    let num_glyphs = read_u16(maxp, 4).ok_or_else(malformed)?;
+    let long_loca = read_u16(head, 50).ok_or_else(malformed)? != 0;
+    let glyph_data = |gid: u16| -> Option<&[u8]> {
+        let (start, end) = if long_loca {
+            let at = gid as usize * 4;
+            (
+                read_u32(loca, at)? as usize,
+                read_u32(loca, at + 4)? as usize,
+            )
+        } else {
+            let at = gid as usize * 2;
+            (
+                read_u16(loca, at)? as usize * 2,
+                read_u16(loca, at + 2)? as usize * 2,
+            )
+        };
+        glyf.get(start..end.max(start))
+    };
+
+    // Close the glyph set over composite glyph components
+    let mut kept: BTreeSet<u16> = glyphs.iter().copied().filter(|&g| g < num_glyphs).collect();
+    kept.insert(0);
+    let mut pending: Vec<u16> = kept.iter().copied().collect();
+    while let Some(gid) = pending.pop() {
+        for component in glyph_data(gid)
+            .map(composite_components)
+            .unwrap_or_default()
+        {
+            if component < num_glyphs && kept.insert(component) {
+                pending.push(component);
+            }
+        }
+    }
+
+    let glyph_count = kept.last().map_or(1, |&last| last + 1);
+    let mut new_glyf = Vec::new();
+    let mut new_loca = Vec::with_capacity((glyph_count as usize + 1) * 4);
+    for gid in 0..glyph_count {
+        new_loca.extend_from_slice(&(new_glyf.len() as u32).to_be_bytes());
+        if kept.contains(&gid) {
+            new_glyf.extend_from_slice(glyph_data(gid).ok_or_else(malformed)?);
+            while new_glyf.len() % 4 != 0 {
+                new_glyf.push(0);
+            }
+        }
+    }
+    new_loca.extend_from_slice(&(new_glyf.len() as u32).to_be_bytes());
+
+    // Glyphs past the last long horizontal metric only store their left side
+    // bearing, so keeping a prefix of hmtx keeps the metrics of every glyph
+    let long_metrics = read_u16(hhea, 34).ok_or_else(malformed)?.min(glyph_count);
+    let hmtx_len = long_metrics as usize * 4 + (glyph_count - long_metrics) as usize * 2;
+    let new_hmtx = hmtx.get(..hmtx_len).ok_or_else(malformed)?.to_vec();
+
+    let mut new_head = head.to_vec();
+    new_head[8..12].fill(0); // checkSumAdjustment, set once the font is assembled
+    new_head[50..52].copy_from_slice(&1u16.to_be_bytes()); // long loca offsets
+    let mut new_maxp = maxp.to_vec();
+    new_maxp[4..6].copy_from_slice(&glyph_count.to_be_bytes());
+    let mut new_hhea = hhea.to_vec();
+    new_hhea[34..36].copy_from_slice(&long_metrics.to_be_bytes());
+
+    let tables: Vec<(&[u8; 4], Vec<u8>)> = KEPT_TABLES
+        .iter()
+        .filter_map(|&tag| {
+            let data = match tag {
+                b"glyf" => std::mem::take(&mut new_glyf),
+                b"head" => std::mem::take(&mut new_head),
+                b"hhea" => std::mem::take(&mut new_hhea),
+                b"hmtx" => new_hmtx.clone(),
+                b"loca" => std::mem::take(&mut new_loca),
+                b"maxp" => std::mem::take(&mut new_maxp),
+                _ => table(tag)?.to_vec(),
+            };
+            Some((tag, data))
+        })
+        .collect();
+
+    Ok(assemble_font(tables))
+}
+
+/// Glyph IDs of the components of a composite glyph (empty for simple glyphs)
+fn composite_components(data: &[u8]) -> Vec<u16> {
+    let mut components = Vec::new();
+    let is_composite = read_u16(data, 0).is_some_and(|contours| (contours as i16) < 0);
+    if !is_composite {
+        return components;
+    }
+
+    let mut at = 10;
+    while let (Some(flags), Some(gid)) = (read_u16(data, at), read_u16(data, at + 2)) {
+        components.push(gid);
+        at += 4;
+        at += if flags & ARG_1_AND_2_ARE_WORDS != 0 {
+            4
+        } else {
+            2
+        };
+        if flags & WE_HAVE_A_SCALE != 0 {
+            at += 2;
+        } else if flags & WE_HAVE_AN_X_AND_Y_SCALE != 0 {
+            at += 4;
+        } else if flags & WE_HAVE_A_TWO_BY_TWO != 0 {
+            at += 8;
+        }
+        if flags & MORE_COMPONENTS == 0 {
+            break;
+        }
+    }
+    components
+}
+
+/// Write an sfnt font file from tables sorted by tag
+fn assemble_font(tables: Vec<(&[u8; 4], Vec<u8>)>) -> Vec<u8> {
+    let num_tables = tables.len() as u16;
+    let entry_selector = 15 - num_tables.max(1).leading_zeros() as u16;
+    let search_range = (1u16 << entry_selector) * 16;
+
+    let mut font = Vec::new();
+    font.extend_from_slice(&0x0001_0000u32.to_be_bytes());
+    font.extend_from_slice(&num_tables.to_be_bytes());
+    font.extend_from_slice(&search_range.to_be_bytes());
+    font.extend_from_slice(&entry_selector.to_be_bytes());
+    font.extend_from_slice(&(num_tables * 16 - search_range).to_be_bytes());
+
+    let mut offset = 12 + tables.len() * 16;
+    let mut head_offset = None;
+    for (tag, data) in &tables {
+        if *tag == b"head" {
+            head_offset = Some(offset);
+        }
+        font.extend_from_slice(*tag);
+        font.extend_from_slice(&checksum(data).to_be_bytes());
+        font.extend_from_slice(&(offset as u32).to_be_bytes());
+        font.extend_from_slice(&(data.len() as u32).to_be_bytes());
+        offset += data.len().next_multiple_of(4);
+    }
+    for (_, data) in &tables {
+        font.extend_from_slice(data);
+        font.resize(font.len().next_multiple_of(4), 0);
+    }
+
+    if let Some(head_offset) = head_offset {
+        let adjustment = 0xB1B0_AFBAu32.wrapping_sub(checksum(&font));
+        font[head_offset + 8..head_offset + 12].copy_from_slice(&adjustment.to_be_bytes());
+    }
+    font
+}
+
+/// TrueType table checksum: the sum of the data as big-endian u32 words
+fn checksum(data: &[u8]) -> u32 {
+    data.chunks(4).fold(0u32, |sum, chunk| {
+        let mut word = [0u8; 4];
+        word[..chunk.len()].copy_from_slice(chunk);
+        sum.wrapping_add(u32::from_be_bytes(word))
+    })
+}
+
+fn read_u16(data: &[u8], at: usize) -> Option<u16> {
+    Some(u16::from_be_bytes(data.get(at..at + 2)?.try_into().ok()?))
+}
+
+fn read_u32(data: &[u8], at: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(data.get(at..at + 4)?.try_into().ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::font::{FontMetrics, TtfFontMetrics};
+
+    #[test]
+    fn test_subset_keeps_glyph_ids_and_metrics() {
+        let Some(font_data) = crate::test_support::dejavu_sans() else {
+            return;
+        };
+        let full = TtfFontMetrics::new(font_data.clone()).unwrap();
+        let used: BTreeSet<u16> = "Hé€".chars().map(|c| full.glyph_id(c).unwrap()).collect();
+
+        let subset = subset_truetype(&font_data, &used).unwrap();
+        assert!(subset.len() < font_data.len() / 4);
+        assert_eq!(checksum(&subset), 0xB1B0_AFBA);
+
+        let face = ttf_parser::Face::parse(&subset, 0).unwrap();
+        assert_eq!(face.number_of_glyphs(), used.last().unwrap() + 1);
+        for ch in "Hé€".chars() {
+            let gid = ttf_parser::GlyphId(full.glyph_id(ch).unwrap());
+            let advance = face.glyph_hor_advance(gid).unwrap();
+            let units_per_em = face.units_per_em() as f32;
+            assert_eq!(
+                advance as f32 / units_per_em * 10.0,
+                full.char_width(ch, 10.0)
+            );
+            assert!(face.glyph_bounding_box(gid).is_some(), "outline of {ch:?}");
+        }
+        // Unused glyphs keep their ID but lose their outline
+        let unused = ttf_parser::GlyphId(full.glyph_id('A').unwrap());
+        assert!(face.glyph_bounding_box(unused).is_none());
+    }
+
+    #[test]
+    fn test_composite_components() {
+        // Two components: word arguments with a scale, then byte arguments
+        let mut glyph = vec![0xFF, 0xFF, 0, 0, 0, 0, 0, 0, 0, 0];
+        glyph.extend_from_slice(
+            &(ARG_1_AND_2_ARE_WORDS | WE_HAVE_A_SCALE | MORE_COMPONENTS).to_be_bytes(),
+        );
+        glyph.extend_from_slice(&7u16.to_be_bytes());
+        glyph.extend_from_slice(&[0; 6]);
+        glyph.extend_from_slice(&0u16.to_be_bytes());
+        glyph.extend_from_slice(&9u16.to_be_bytes());
+        glyph.extend_from_slice(&[0; 2]);
+
+        assert_eq!(composite_components(&glyph), vec![7, 9]);
+        assert!(composite_components(&[0, 1, 0, 0]).is_empty());
+    }
+}
@@ -0,0 +1,237 @@
+//! Cell placement grid resolving colspan and rowspan
+
+use crate::Result;
+use crate::error::TableError;
+use crate::table::Table;
+
+/// Marker for a grid slot not yet claimed by any cell
+const EMPTY_SLOT: usize = usize::MAX;
+
+/// Resolved position of a single cell within the table grid
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct CellPlacement {
+    /// Row the cell is declared in
+    pub row: usize,
+    /// First column covered by the cell
+    pub col: usize,
+    /// Index of the cell within its row's `cells`
+    pub cell: usize,
+    pub colspan: usize,
+    pub rowspan: usize,
+}
+
+impl CellPlacement {
+    /// Last row covered by this cell (inclusive)
+    pub fn last_row(&self) -> usize {
+        self.row + self.rowspan - 1
+    }
+
+    /// Whether this cell covers the given row
+    pub fn covers_row(&self, row: usize) -> bool {
+        row >= self.row && row <= self.last_row()
+    }
+}
+
+/// Occupancy grid mapping every (row, column) slot to the cell covering it.
+///
+/// Rows that sit under a cell spanning down from an earlier row declare fewer
+/// cells; their cells fill the remaining free slots from left to right.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct CellGrid {
+    columns: usize,
+    placements: Vec<CellPlacement>,
+    /// Offset into `placements` of the first cell declared in each row (len = rows + 1)
+    row_offsets: Vec<usize>,
+    /// Placement index occupying each slot, row-major
+    slots: Vec<usize>,
+}
+
+impl CellGrid {
+    /// Resolve the grid for a table, rejecting rows that do not tile the columns exactly.
+    pub fn new(table: &Table) -> Result<Self> {
+        let columns = table.column_count();
+        let row_count = table.rows.len();
+        let mut grid = Self {
+            columns,
+            placements: Vec::new(),
+            row_offsets: Vec::with_capacity(row_count + 1),
+            slots: vec![EMPTY_SLOT; row_count * columns],
+        };
+
+        for (row_idx, row) in table.rows.iter().enumerate() {
+            grid.row_offsets.push(grid.placements.len());
+
+            let row_slots = &grid.slots[row_idx * columns..(row_idx + 1) * columns];
+            let spanned_from_above = row_slots.iter().filter(|&&s| s != EMPTY_SLOT).count();
+            let declared: usize = row.cells.iter().map(|c| c.colspan.max(1)).sum();
+            let total_coverage = spanned_from_above + declared;
+            if total_coverage != columns {
+                return Err(TableError::InvalidTable(format!(
+                    "Row {} covers {} columns (with colspan and rowspan), expected {}",
+                    row_idx, total_coverage, columns
+                )));
+            }
+
+            let mut col = 0;
+            for (cell_idx, cell) in row.cells.iter().enumerate() {
+                while col < columns && grid.slots[row_idx * columns + col] != EMPTY_SLOT {
+                    col += 1;
+                }
+
+                let colspan = cell.colspan.max(1);
+                let rowspan = cell.rowspan.max(1);
+                if row_idx + rowspan > row_count {
+                    return Err(TableError::InvalidTable(format!(
+                        "Cell {} in row {} spans {} rows, but only {} rows remain",
+                        cell_idx,
+                        row_idx,
+                        rowspan,
+                        row_count - row_idx
+                    )));
+                }
+
+                let placement_idx = grid.placements.len();
+                for r in row_idx..row_idx + rowspan {
+                    for c in col..col + colspan {
+                        let slot = &mut grid.slots[r * columns + c];
+                        if *slot != EMPTY_SLOT {
+                            return Err(TableError::InvalidTable(format!(
+                                "Cell {} in row {} overlaps a cell spanning from an earlier row",
+                                cell_idx, row_idx
+                            )));
+                        }
+                        *slot = placement_idx;
+                    }
+                }
+
+                grid.placements.push(CellPlacement {
+                    row: row_idx,
+                    col,
+                    cell: cell_idx,
+                    colspan,
+                    rowspan,
+                });
+                col += colspan;
+            }
+        }
+        grid.row_offsets.push(grid.placements.len());
+
+        Ok(grid)
+    }
+
+    /// Number of columns in the grid
+    pub fn columns(&self) -> usize {
+        self.columns
+    }
+
+    /// Number of rows in the grid
+    pub fn rows(&self) -> usize {
+        self.row_offsets.len().saturating_sub(1)
+    }
+
+    /// All resolved cell placements, in declaration order
+    pub fn placements(&self) -> &[CellPlacement] {
+        &self.placements
+    }
+
+    /// Cells declared in the given row, in declaration order
+    pub fn placements_in_row(&self, row: usize) -> &[CellPlacement] {
+        &self.placements[self.row_offsets[row]..self.row_offsets[row + 1]]
+    }
+
+    /// Index of the placement covering a slot
+    pub fn slot(&self, row: usize, col: usize) -> usize {
+        self.slots[row * self.columns + col]
+    }
+
+    /// Placement covering a slot
+    pub fn placement_at(&self, row: usize, col: usize) -> &CellPlacement {
+        &self.placements[self.slot(row, col)]
+    }
+
+    /// Distinct placements covering a row, left to right (including cells spanning from above)
+    pub fn placements_covering_row(&self, row: usize) -> impl Iterator<Item = &CellPlacement> {
+        let mut col = 0;
+        std::iter::from_fn(move || {
+            if col >= self.columns {
+                return None;
+            }
+            let placement = self.placement_at(row, col);
+            col += placement.colspan;
+            Some(placement)
+        })
+    }
+
+    /// Whether a cell spans across the boundary between `row - 1` and `row`
+    pub fn spans_into(&self, row: usize) -> bool {
+        row > 0
+            && row < self.rows()
+            && (0..self.columns).any(|c| self.slot(row, c) == self.slot(row - 1, c))
+    }
+
+    /// End (exclusive) of the group of rows starting at `row` that rowspans tie together
+    pub fn group_end(&self, row: usize) -> usize {
+        let mut end = row + 1;
+        while self.spans_into(end) {
+            end += 1;
+        }
+        end
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::table::{Cell, Row};
+
+    #[test]
+    fn test_rowspan_fills_following_rows() {
+        let table = Table::new()
+            .add_row(Row::new(vec![
+                Cell::new("A").with_rowspan(2),
+                Cell::new("B"),
+                Cell::new("C"),
+            ]))
+            .add_row(Row::new(vec![Cell::new("D"), Cell::new("E")]))
+            .add_row(Row::new(vec![
+                Cell::new("F"),
+                Cell::new("G"),
+                Cell::new("H"),
+            ]));
+
+        let grid = CellGrid::new(&table).unwrap();
+        assert_eq!(grid.columns(), 3);
+        assert_eq!(grid.placements_in_row(1)[0].col, 1);
+        assert_eq!(grid.placement_at(1, 0).row, 0);
+        assert!(grid.spans_into(1));
+        assert!(!grid.spans_into(2));
+        assert_eq!(grid.group_end(0), 2);
+        assert_eq!(grid.group_end(2), 3);
+        assert_eq!(grid.placements_covering_row(1).count(), 3);
+    }
+
+    #[test]
+    fn test_rowspan_past_last_row_is_rejected() {
+        let table = Table::new()
+            .add_row(Row::new(vec![
+                Cell::new("A").with_rowspan(3),
+                Cell::new("B"),
+            ]))
+            .add_row(Row::new(vec![Cell::new("C")]));
+
+        assert!(CellGrid::new(&table).is_err());
+    }
+
+    #[test]
+    fn test_colspan_overlapping_rowspan_is_rejected() {
+        let table = Table::new()
+            .add_row(Row::new(vec![
+                Cell::new("A"),
+                Cell::new("B").with_rowspan(2),
+                Cell::new("C"),
+            ]))
+            .add_row(Row::new(vec![Cell::new("D").with_colspan(2)]));
+
+        assert!(CellGrid::new(&table).is_err());
+    }
+}
@@ -3,40 +3,104 @@
 use crate::Result;
 use crate::constants::*;
 use crate::error::TableError;
+use crate::grid::CellGrid;
+use crate::page::PageBox;
+use crate::standard_fonts::StandardFont;
 use crate::table::{ColumnWidth, Table};
+use std::hash::{DefaultHasher, Hasher};
 use tracing::{debug, trace};
 
-/// Calculated layout information for a table
+/// Calculated layout information for a table.
+///
+/// Only built by [`calculate_layout`] and [`calculate_layout_for_page`], for
+/// the table it is then drawn with.
 #[derive(Debug, Clone)]
+#[non_exhaustive]
 pub struct TableLayout {
     pub column_widths: Vec<f32>,
     pub row_heights: Vec<f32>,
     pub total_width: f32,
     pub total_height: f32,
+    /// Resolved colspan/rowspan placement of every cell
+    pub(crate) grid: CellGrid,
+    /// Text lines of every cell, indexed by row and then by cell within the row
+    pub(crate) text_lines: Vec<Vec<Vec<TextLine>>>,
+    /// Hash of the content and styles of the table laid out
+    pub(crate) fingerprint: u64,
+}
+
+/// A line of cell text as wrapped during layout
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextLine {
+    pub text: String,
+    /// Measured width of the text at the cell's font size
+    pub width: f32,
+}
+
+impl TableLayout {
+    /// Check that this layout was computed for a table with the same grid,
+    /// cell text and styles
+    pub(crate) fn check_table(&self, table: &Table) -> Result<()> {
+        let grid = CellGrid::new(table)?;
+        if grid.rows() != self.row_heights.len() || grid.columns() != self.column_widths.len() {
+            return Err(TableError::LayoutError(format!(
+                "Layout of {} rows and {} columns does not match table of {} rows and {} columns",
+                self.row_heights.len(),
+                self.column_widths.len(),
+                grid.rows(),
+                grid.columns()
+            )));
+        }
+        if table_fingerprint(table) != self.fingerprint {
+            return Err(TableError::LayoutError(
+                "Layout was computed for a table with other content or styles".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Text lines of a cell as drawn, or an empty slice for empty cells
+    pub fn text_lines(&self, row: usize, cell: usize) -> &[TextLine] {
+        self.text_lines
+            .get(row)
+            .and_then(|cells| cells.get(cell))
+            .map_or(&[], |lines| lines.as_slice())
+    }
 }
 
 fn cell_is_bold(cell: &crate::table::Cell) -> bool {
     cell.style.as_ref().map(|s| s.bold).unwrap_or(false)
 }
 
+fn cell_is_italic(cell: &crate::table::Cell) -> bool {
+    cell.style.as_ref().map(|s| s.italic).unwrap_or(false)
+}
+
+/// Metrics a cell's text is measured with: the table's font metrics, or
+/// else the standard font the cell is drawn with
 fn metrics_for_cell<'a>(
     table: &'a Table,
     cell: &crate::table::Cell,
-) -> Option<&'a dyn crate::font::FontMetrics> {
-    if cell_is_bold(cell) {
-        table
-            .bold_font_metrics
-            .as_ref()
-            .map(|m| m.as_ref())
-            .or(table.font_metrics.as_ref().map(|m| m.as_ref()))
-    } else {
-        table.font_metrics.as_ref().map(|m| m.as_ref())
+) -> &'a dyn crate::font::FontMetrics {
+    match table.font_metrics_for(cell_is_bold(cell), cell_is_italic(cell)) {
+        Some(metrics) => metrics,
+        None => StandardFont::for_cell(table, cell).metrics(),
     }
 }
 
-/// Calculate the layout for a table
+/// Calculate the layout for a table drawn on an A4 page.
+///
+/// Use [`calculate_layout_for_page`] for tables drawn on other page sizes.
 pub fn calculate_layout(table: &Table) -> Result<TableLayout> {
-    table.validate()?;
+    calculate_layout_for_page(table, &PageBox::default())
+}
+
+/// Calculate the layout for a table drawn on a page with the given visible area.
+///
+/// Without a `total_width`, the table may use the page width between the
+/// style's left and right margins.
+pub fn calculate_layout_for_page(table: &Table, page: &PageBox) -> Result<TableLayout> {
+    let grid = table.validated_grid()?;
 
     debug!(
         "Calculating layout for table with {} rows",
@@ -44,20 +108,22 @@ pub fn calculate_layout(table: &Table) -> Result<TableLayout> {
     );
 
     // Determine the available table width
-    let available_width = table.total_width.unwrap_or_else(|| {
-        // If no total width specified, calculate based on content
-        estimate_total_width(table)
-    });
+    let available_width = table
+        .total_width
+        .unwrap_or_else(|| estimate_total_width(table, page));
 
     // Calculate column widths based on specifications
     let column_widths = if let Some(ref width_specs) = table.column_widths {
-        resolve_column_widths(width_specs, available_width, table)?
+        resolve_column_widths(width_specs, available_width, table, &grid)?
     } else {
-        calculate_column_widths(table)?
+        calculate_column_widths(table, &grid, available_width)?
     };
 
-    // Calculate row heights (considering text wrapping if enabled)
-    let row_heights = calculate_row_heights(table, &column_widths)?;
+    // Wrap every cell's text once; drawing reuses the same lines
+    let text_lines = wrap_cell_texts(table, &grid, &column_widths);
+
+    // Calculate row heights (considering text wrapping and rowspans)
+    let row_heights = calculate_row_heights(table, &grid, &column_widths, &text_lines)?;
 
     // Calculate totals
     let total_width = column_widths.iter().sum();
@@ -70,167 +136,356 @@ pub fn calculate_layout(table: &Table) -> Result<TableLayout> {
         row_heights,
         total_width,
         total_height,
+        grid,
+        text_lines,
+        fingerprint: table_fingerprint(table),
     })
 }
 
-/// Estimate total table width based on content
-fn estimate_total_width(_table: &Table) -> f32 {
-    // Default to a reasonable page width minus margins
-    // Standard US Letter is 612 points wide, leave 50 points margin on each side
-    LETTER_WIDTH - (DEFAULT_MARGIN * 2.0)
+/// Hash of what a table's layout depends on besides the page and font
+/// metrics: its width settings, style, and the content and style of every
+/// row and cell
+pub(crate) fn table_fingerprint(table: &Table) -> u64 {
+    use std::fmt::Write;
+
+    /// Feeds formatted output to a hasher without building strings
+    struct HashWriter(DefaultHasher);
+
+    impl Write for HashWriter {
+        fn write_str(&mut self, s: &str) -> std::fmt::Result {
+            self.0.write(s.as_bytes());
+            Ok(())
+        }
+    }
+
+    let mut writer = HashWriter(DefaultHasher::new());
+    // Writing to a hasher cannot fail
+    let _ = write!(
+        writer,
+        "{:?}{:?}{:?}{:?}",
+        table.column_widths, table.total_width, table.style, table.rows
+    );
+    writer.0.finish()
+}
+
+/// Width available to a table on the page, between the left and right margins
+fn estimate_total_width(table: &Table, page: &PageBox) -> f32 {
+    (page.width() - table.style.left_margin - table.style.right_margin).max(MIN_COLUMN_WIDTH)
 }
 
-/// Resolve column widths from specifications
+/// Resolve column widths from specifications.
+///
+/// Pixel and percentage columns are fixed. Auto columns start at their
+/// max-content width and `MinMax` columns at their preferred width (clamped),
+/// while fraction columns start at their min-content width. The leftover
+/// space then goes to fraction columns by weight, or else to auto columns in
+/// proportion to their content, or else to `MinMax` columns up to their max.
+/// When the columns overflow instead, auto and `MinMax` columns shrink towards
+/// their min-content and min widths.
 fn resolve_column_widths(
     specs: &[ColumnWidth],
     available_width: f32,
     table: &Table,
+    grid: &CellGrid,
 ) -> Result<Vec<f32>> {
+    let content_widths = column_content_widths(table, grid);
     let mut resolved_widths = vec![0.0; specs.len()];
-    let mut total_fixed_width = 0.0;
-    let mut total_percentage = 0.0;
-    let mut auto_columns = Vec::new();
+    // Lowest width each flexible column may shrink to (fixed columns keep theirs)
+    let mut lower_bounds = vec![0.0; specs.len()];
 
-    // First pass: calculate fixed widths and percentages
     for (i, spec) in specs.iter().enumerate() {
-        match spec {
-            ColumnWidth::Pixels(width) => {
-                resolved_widths[i] = *width;
-                total_fixed_width += width;
-            }
+        let content = content_widths.get(i).copied().unwrap_or_default();
+        let (width, lower) = match spec {
+            ColumnWidth::Pixels(width) => (*width, *width),
             ColumnWidth::Percentage(percent) => {
-                total_percentage += percent;
+                let width = available_width * (percent / 100.0);
+                (width, width)
             }
-            ColumnWidth::Auto => {
-                auto_columns.push(i);
-            }
-        }
+            ColumnWidth::Auto => (content.max, content.min),
+            ColumnWidth::MinMax {
+                min,
+                max,
+                preferred,
+            } => (preferred.unwrap_or(content.max).clamp(*min, *max), *min),
+            ColumnWidth::Fraction(_) => (content.min, content.min),
+        };
+        resolved_widths[i] = width;
+        lower_bounds[i] = lower;
     }
 
-    // Calculate remaining width for auto columns
-    let percentage_width = available_width * (total_percentage / 100.0);
-    let remaining_width = available_width - total_fixed_width - percentage_width;
-
-    // Second pass: resolve percentage widths
-    for (i, spec) in specs.iter().enumerate() {
-        if let ColumnWidth::Percentage(percent) = spec {
-            resolved_widths[i] = available_width * (percent / 100.0);
+    let leftover = available_width - resolved_widths.iter().sum::<f32>();
+    let columns_where = |matches: fn(&ColumnWidth) -> bool| -> Vec<usize> {
+        (0..specs.len()).filter(|&i| matches(&specs[i])).collect()
+    };
+    let fraction_columns = columns_where(|s| matches!(s, ColumnWidth::Fraction(_)));
+    let auto_columns = columns_where(|s| matches!(s, ColumnWidth::Auto));
+    let minmax_columns = columns_where(|s| matches!(s, ColumnWidth::MinMax { .. }));
+
+    if leftover < 0.0 {
+        shrink_towards(&mut resolved_widths, &lower_bounds, -leftover);
+    } else if !fraction_columns.is_empty() {
+        size_fractions(specs, &fraction_columns, &mut resolved_widths, leftover);
+    } else if !auto_columns.is_empty() {
+        let weights: Vec<f32> = auto_columns.iter().map(|&c| resolved_widths[c]).collect();
+        let total: f32 = weights.iter().sum();
+        for (&col, weight) in auto_columns.iter().zip(weights) {
+            resolved_widths[col] += leftover * weight / total;
         }
+    } else if !minmax_columns.is_empty() {
+        grow_towards_max(specs, &minmax_columns, &mut resolved_widths, leftover);
     }
 
-    // Third pass: distribute remaining width among auto columns
-    if !auto_columns.is_empty() {
-        if remaining_width > 0.0 {
-            // Calculate content-based proportions for auto columns
-            let mut auto_proportions = vec![0.0; auto_columns.len()];
-            let mut total_proportion = 0.0;
+    trace!("Resolved column widths: {:?}", resolved_widths);
+    Ok(resolved_widths)
+}
 
-            for (idx, &col) in auto_columns.iter().enumerate() {
-                // Estimate width based on content
-                let max_content_width = estimate_column_content_width(table, col);
-                auto_proportions[idx] = max_content_width;
-                total_proportion += max_content_width;
-            }
+/// Narrow columns by `deficit` in proportion to how far each sits above its
+/// lower bound, never going below it
+fn shrink_towards(widths: &mut [f32], lower_bounds: &[f32], deficit: f32) {
+    let shrinkable: f32 = widths
+        .iter()
+        .zip(lower_bounds)
+        .map(|(w, lower)| (w - lower).max(0.0))
+        .sum();
+    if shrinkable <= 0.0 {
+        return;
+    }
+    let ratio = (deficit / shrinkable).min(1.0);
+    for (width, lower) in widths.iter_mut().zip(lower_bounds) {
+        *width -= (*width - lower).max(0.0) * ratio;
+    }
+}
 
-            // Distribute remaining width proportionally
-            for (idx, &col) in auto_columns.iter().enumerate() {
-                if total_proportion > 0.0 {
-                    resolved_widths[col] =
-                        remaining_width * (auto_proportions[idx] / total_proportion);
-                } else {
-                    resolved_widths[col] = remaining_width / auto_columns.len() as f32;
-                }
-                // Ensure minimum width
-                resolved_widths[col] = resolved_widths[col].max(MIN_COLUMN_WIDTH);
-            }
-        } else {
-            // If no remaining width, give auto columns a minimum width
-            for &col in &auto_columns {
-                resolved_widths[col] = MIN_COLUMN_WIDTH;
+/// Share the leftover space (plus their floors) between fraction columns by
+/// weight. A column whose share falls below its min-content floor keeps the
+/// floor and the others are sized again without it.
+fn size_fractions(specs: &[ColumnWidth], columns: &[usize], widths: &mut [f32], leftover: f32) {
+    let weight = |col: usize| match specs[col] {
+        ColumnWidth::Fraction(weight) => weight,
+        _ => 0.0,
+    };
+    let mut space = leftover + columns.iter().map(|&c| widths[c]).sum::<f32>();
+    let mut active: Vec<usize> = columns.to_vec();
+
+    loop {
+        let total_weight: f32 = active.iter().map(|&c| weight(c)).sum();
+        let unit = space / total_weight;
+        let (frozen, rest): (Vec<usize>, Vec<usize>) =
+            active.iter().partition(|&&c| weight(c) * unit < widths[c]);
+        if frozen.is_empty() {
+            for &col in &active {
+                widths[col] = weight(col) * unit;
             }
+            return;
+        }
+        space -= frozen.iter().map(|&c| widths[c]).sum::<f32>();
+        active = rest;
+        if active.is_empty() {
+            return;
         }
     }
-
-    trace!("Resolved column widths: {:?}", resolved_widths);
-    Ok(resolved_widths)
 }
 
-/// Estimate content width for a specific column
-fn estimate_column_content_width(table: &Table, col_idx: usize) -> f32 {
-    let mut max_width = 0.0;
-
-    for row in &table.rows {
-        if col_idx < row.cells.len() {
-            let cell = &row.cells[col_idx];
-            let font_size = cell
-                .style
-                .as_ref()
-                .and_then(|s| s.font_size)
-                .unwrap_or(table.style.default_font_size);
-
-            let estimated_width = if let Some(metrics) = metrics_for_cell(table, cell) {
-                crate::drawing_utils::estimate_text_width_with_metrics(
-                    &cell.content,
-                    font_size,
-                    metrics,
-                )
-            } else {
-                crate::drawing_utils::estimate_text_width(&cell.content, font_size)
-            };
-            max_width = f32::max(max_width, estimated_width);
+/// Widen `MinMax` columns by `amount` in proportion to their width, each up
+/// to its max; space a capped column cannot take goes to the others.
+fn grow_towards_max(specs: &[ColumnWidth], columns: &[usize], widths: &mut [f32], amount: f32) {
+    let max = |col: usize| match specs[col] {
+        ColumnWidth::MinMax { max, .. } => max,
+        _ => 0.0,
+    };
+    let mut amount = amount;
+    let mut active: Vec<usize> = columns
+        .iter()
+        .copied()
+        .filter(|&c| widths[c] < max(c))
+        .collect();
+
+    while amount > 0.001 && !active.is_empty() {
+        let total: f32 = active.iter().map(|&c| widths[c].max(f32::EPSILON)).sum();
+        let mut used = 0.0;
+        for &col in &active {
+            let share = amount * widths[col].max(f32::EPSILON) / total;
+            let grown = (widths[col] + share).min(max(col));
+            used += grown - widths[col];
+            widths[col] = grown;
         }
+        amount -= used;
+        active.retain(|&c| widths[c] < max(c));
     }
-
-    // Add padding
-    let padding = table.style.padding.left + table.style.padding.right;
-    max_width + padding
 }
 
-/// Calculate automatic column widths based on content
-fn calculate_column_widths(table: &Table) -> Result<Vec<f32>> {
-    let col_count = table.column_count();
-    if col_count == 0 {
-        return Err(TableError::LayoutError("No columns in table".to_string()));
-    }
+/// Min-content and max-content widths of a cell or column, including padding
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct ContentWidths {
+    /// Narrowest width without overflow: the longest unbreakable word when
+    /// wrapping, otherwise the widest line
+    min: f32,
+    /// Width needed to lay out the content without wrapping
+    max: f32,
+}
 
-    let mut max_widths = vec![0.0; col_count];
+fn measure_text(table: &Table, cell: &crate::table::Cell, text: &str, font_size: f32) -> f32 {
+    crate::drawing_utils::estimate_text_width_with_metrics(
+        text,
+        font_size,
+        metrics_for_cell(table, cell),
+    )
+}
 
-    for row in &table.rows {
-        for (i, cell) in row.cells.iter().enumerate() {
-            if i >= col_count {
-                break;
+/// Widest rendering of a cell's images side by side; they scale down freely
+fn images_max_content_width(images: &[crate::table::CellImage]) -> f32 {
+    const IMAGE_GAP: f32 = 4.0;
+    let widths: f32 = images
+        .iter()
+        .map(|image| {
+            let natural = image.width_px as f32;
+            match image.max_render_height_pts {
+                Some(max_h) if image.height_px > 0 => natural.min(max_h * image.aspect_ratio()),
+                _ => natural,
             }
+        })
+        .sum();
+    widths + IMAGE_GAP * images.len().saturating_sub(1) as f32
+}
+
+fn cell_content_widths(table: &Table, cell: &crate::table::Cell) -> ContentWidths {
+    let font_size = cell
+        .style
+        .as_ref()
+        .and_then(|s| s.font_size)
+        .unwrap_or(table.style.default_font_size);
+    let padding = cell
+        .style
+        .as_ref()
+        .and_then(|s| s.padding.as_ref())
+        .unwrap_or(&table.style.padding);
+    let horizontal_padding = padding.left + padding.right;
+
+    let max_line = cell
+        .content
+        .split('\n')
+        .map(|line| measure_text(table, cell, line, font_size))
+        .fold(0.0, f32::max);
+    let min_text = if cell.text_wrap {
+        cell.content
+            .split_whitespace()
+            .map(|word| measure_text(table, cell, word, font_size))
+            .fold(0.0, f32::max)
+    } else {
+        max_line
+    };
+    let max_images = images_max_content_width(&cell.images);
+
+    ContentWidths {
+        min: min_text + horizontal_padding,
+        max: max_line.max(max_images) + horizontal_padding,
+    }
+}
+
+/// Min- and max-content widths of every column.
+///
+/// Cells spanning one column size it directly; wider spans then grow the
+/// columns they cover, in proportion to their max-content widths, when the
+/// spanned columns are too narrow for them.
+fn column_content_widths(table: &Table, grid: &CellGrid) -> Vec<ContentWidths> {
+    let mut columns = vec![ContentWidths::default(); grid.columns()];
+
+    let mut spanning = Vec::new();
+    for placement in grid.placements() {
+        let cell = &table.rows[placement.row].cells[placement.cell];
+        let widths = cell_content_widths(table, cell);
+        if placement.colspan > 1 {
+            spanning.push((placement.col..placement.col + placement.colspan, widths));
+        } else {
+            let column = &mut columns[placement.col];
+            column.min = column.min.max(widths.min);
+            column.max = column.max.max(widths.max);
+        }
+    }
 
-            let font_size = cell
-                .style
-                .as_ref()
-                .and_then(|s| s.font_size)
-                .unwrap_or(table.style.default_font_size);
-
-            let estimated_width = if let Some(metrics) = metrics_for_cell(table, cell) {
-                crate::drawing_utils::estimate_text_width_with_metrics(
-                    &cell.content,
-                    font_size,
-                    metrics,
-                )
+    spanning.sort_by_key(|(cols, _)| cols.len());
+    for (cols, widths) in spanning {
+        let cols = cols.start..cols.end.min(columns.len());
+        let spanned_max: f32 = columns[cols.clone()].iter().map(|c| c.max).sum();
+        let share = |c: &ContentWidths| {
+            if spanned_max > 0.0 {
+                c.max / spanned_max
             } else {
-                crate::drawing_utils::estimate_text_width(&cell.content, font_size)
-            };
+                1.0 / cols.len() as f32
+            }
+        };
 
-            max_widths[i] = f32::max(max_widths[i], estimated_width);
+        let spanned_min: f32 = columns[cols.clone()].iter().map(|c| c.min).sum();
+        if widths.min > spanned_min {
+            let extra = widths.min - spanned_min;
+            for column in &mut columns[cols.clone()] {
+                column.min += extra * share(column);
+            }
+        }
+        if widths.max > spanned_max {
+            let extra = widths.max - spanned_max;
+            let shares: Vec<f32> = columns[cols.clone()].iter().map(share).collect();
+            for (column, share) in columns[cols.clone()].iter_mut().zip(shares) {
+                column.max += extra * share;
+            }
         }
     }
 
-    // Add padding
-    let padding = table.style.padding.left + table.style.padding.right;
-    for width in &mut max_widths {
-        *width += padding;
-        // Ensure minimum width
-        *width = width.max(MIN_COLUMN_WIDTH);
+    for column in &mut columns {
+        column.min = column.min.max(MIN_COLUMN_WIDTH);
+        column.max = column.max.max(column.min);
     }
+    columns
+}
 
-    trace!("Calculated column widths: {:?}", max_widths);
-    Ok(max_widths)
+/// Fit columns into `available` width, CSS auto table layout style.
+///
+/// Columns get their max-content width when everything fits (growing
+/// proportionally to fill `available` when `fill` is set). Otherwise they
+/// shrink from max- towards min-content width by the same fraction, and never
+/// below min-content width, even if the table then exceeds `available`.
+fn fit_content_widths(columns: &[ContentWidths], available: f32, fill: bool) -> Vec<f32> {
+    let total_min: f32 = columns.iter().map(|c| c.min).sum();
+    let total_max: f32 = columns.iter().map(|c| c.max).sum();
+
+    if total_max <= available {
+        let extra = if fill { available - total_max } else { 0.0 };
+        columns
+            .iter()
+            .map(|c| c.max + extra * c.max / total_max)
+            .collect()
+    } else if total_min < available {
+        let ratio = (available - total_min) / (total_max - total_min);
+        columns
+            .iter()
+            .map(|c| c.min + (c.max - c.min) * ratio)
+            .collect()
+    } else {
+        columns.iter().map(|c| c.min).collect()
+    }
+}
+
+/// Calculate automatic column widths based on content, fitted to the
+/// available width (filling `Table::total_width` when it is set)
+fn calculate_column_widths(
+    table: &Table,
+    grid: &CellGrid,
+    available_width: f32,
+) -> Result<Vec<f32>> {
+    let col_count = grid.columns();
+    if col_count == 0 {
+        return Err(TableError::LayoutError("No columns in table".to_string()));
+    }
+
+    let content_widths = column_content_widths(table, grid);
+    let widths = fit_content_widths(
+        &content_widths,
+        available_width,
+        table.total_width.is_some(),
+    );
+
+    trace!("Calculated column widths: {:?}", widths);
+    Ok(widths)
 }
 
 /// Compute image-driven content height for a single image using contain-fit.
@@ -266,76 +521,188 @@ fn images_content_height(images: &[crate::table::CellImage], available_width: f3
         .fold(0.0f32, f32::max)
 }
 
-/// Calculate row heights based on content
-fn calculate_row_heights(table: &Table, column_widths: &[f32]) -> Result<Vec<f32>> {
+/// Lines of text a cell renders at the given cell width (empty for empty cells)
+pub(crate) fn cell_text_lines(
+    table: &Table,
+    cell: &crate::table::Cell,
+    cell_width: f32,
+) -> Vec<TextLine> {
+    if cell.content.is_empty() {
+        return Vec::new();
+    }
+
+    let padding = cell
+        .style
+        .as_ref()
+        .and_then(|s| s.padding.as_ref())
+        .unwrap_or(&table.style.padding);
+    let font_size = cell
+        .style
+        .as_ref()
+        .and_then(|s| s.font_size)
+        .unwrap_or(table.style.default_font_size);
+    let available_width = cell_width - padding.left - padding.right;
+    let metrics = metrics_for_cell(table, cell);
+
+    let lines = if cell.text_wrap {
+        crate::text::wrap_text_with_metrics(&cell.content, available_width, font_size, metrics)
+    } else {
+        // Split by newlines even when wrapping is off, to handle embedded newlines
+        cell.content.split('\n').map(|s| s.to_string()).collect()
+    };
+
+    lines
+        .into_iter()
+        .map(|text| {
+            let width = metrics.text_width(&text, font_size);
+            TextLine { text, width }
+        })
+        .collect()
+}
+
+/// Wrap the text of every cell at the width of the columns it spans
+fn wrap_cell_texts(
+    table: &Table,
+    grid: &CellGrid,
+    column_widths: &[f32],
+) -> Vec<Vec<Vec<TextLine>>> {
+    let mut text_lines: Vec<Vec<Vec<TextLine>>> = table
+        .rows
+        .iter()
+        .map(|row| vec![Vec::new(); row.cells.len()])
+        .collect();
+
+    for placement in grid.placements() {
+        let cell = &table.rows[placement.row].cells[placement.cell];
+        let cell_width = crate::drawing_utils::calculate_cell_width(
+            placement.col,
+            placement.colspan,
+            column_widths,
+        );
+        text_lines[placement.row][placement.cell] = cell_text_lines(table, cell, cell_width);
+    }
+
+    text_lines
+}
+
+/// Height a cell's content needs at the given cell width, excluding the row padding
+fn cell_content_height(
+    table: &Table,
+    cell: &crate::table::Cell,
+    cell_width: f32,
+    lines: &[TextLine],
+) -> f32 {
+    let padding = cell
+        .style
+        .as_ref()
+        .and_then(|s| s.padding.as_ref())
+        .unwrap_or(&table.style.padding);
+
+    let font_size = cell
+        .style
+        .as_ref()
+        .and_then(|s| s.font_size)
+        .unwrap_or(table.style.default_font_size);
+
+    // Calculate available width for content
+    let available_width = cell_width - padding.left - padding.right;
+
+    // Text-driven height; wrapped text takes at least one line, even when empty
+    let text_height = if cell.text_wrap {
+        lines.len().max(1) as f32 * font_size * DEFAULT_LINE_HEIGHT_MULTIPLIER
+    } else if !cell.content.is_empty() {
+        font_size_to_height(font_size)
+    } else {
+        0.0
+    };
+
+    // Image-driven height
+    let img_height = images_content_height(&cell.images, available_width);
+
+    f32::max(text_height, img_height)
+}
+
+/// Calculate row heights based on content.
+///
+/// Cells spanning a single row size their row directly. Cells with
+/// `rowspan > 1` are resolved afterwards: when the spanned rows are too short
+/// for their content, the shortfall is spread evenly across the spanned rows
+/// that have no explicit height.
+fn calculate_row_heights(
+    table: &Table,
+    grid: &CellGrid,
+    column_widths: &[f32],
+    text_lines: &[Vec<Vec<TextLine>>],
+) -> Result<Vec<f32>> {
+    let vertical_padding = table.style.padding.top + table.style.padding.bottom;
+    let min_height = font_size_to_height(table.style.default_font_size);
     let mut heights = Vec::with_capacity(table.rows.len());
 
-    for row in &table.rows {
+    for (row_idx, row) in table.rows.iter().enumerate() {
         if let Some(height) = row.height {
             heights.push(height);
         } else {
             // Calculate based on content
             let mut max_height = 0.0;
 
-            for (i, cell) in row.cells.iter().enumerate() {
-                if i >= column_widths.len() {
-                    break;
+            for placement in grid.placements_in_row(row_idx) {
+                if placement.rowspan > 1 {
+                    continue;
                 }
-
-                let padding = cell
-                    .style
-                    .as_ref()
-                    .and_then(|s| s.padding.as_ref())
-                    .unwrap_or(&table.style.padding);
-
-                let font_size = cell
-                    .style
-                    .as_ref()
-                    .and_then(|s| s.font_size)
-                    .unwrap_or(table.style.default_font_size);
-
-                // Calculate available width for content
-                let available_width = column_widths[i] - padding.left - padding.right;
-
-                // Text-driven height
-                let text_height = if cell.text_wrap {
-                    if let Some(metrics) = metrics_for_cell(table, cell) {
-                        crate::text::calculate_wrapped_text_height_with_metrics(
-                            &cell.content,
-                            available_width,
-                            font_size,
-                            DEFAULT_LINE_HEIGHT_MULTIPLIER,
-                            metrics,
-                        )
-                    } else {
-                        crate::text::calculate_wrapped_text_height(
-                            &cell.content,
-                            available_width,
-                            font_size,
-                            DEFAULT_LINE_HEIGHT_MULTIPLIER,
-                        )
-                    }
-                } else if !cell.content.is_empty() {
-                    font_size_to_height(font_size)
-                } else {
-                    0.0
-                };
-
-                // Image-driven height
-                let img_height = images_content_height(&cell.images, available_width);
-
-                max_height = f32::max(max_height, f32::max(text_height, img_height));
+                let cell = &row.cells[placement.cell];
+                let cell_width = crate::drawing_utils::calculate_cell_width(
+                    placement.col,
+                    placement.colspan,
+                    column_widths,
+                );
+                let lines = &text_lines[row_idx][placement.cell];
+                max_height = f32::max(
+                    max_height,
+                    cell_content_height(table, cell, cell_width, lines),
+                );
             }
 
             // Add padding
-            max_height += table.style.padding.top + table.style.padding.bottom;
+            max_height += vertical_padding;
             // Ensure minimum height
-            max_height = max_height.max(font_size_to_height(table.style.default_font_size));
+            max_height = max_height.max(min_height);
 
             heights.push(max_height);
         }
     }
 
+    // Resolve spanning cells, shortest spans first so wider spans see the final
+    // heights of the rows they enclose.
+    let mut spanning: Vec<_> = grid.placements().iter().filter(|p| p.rowspan > 1).collect();
+    spanning.sort_by_key(|p| p.rowspan);
+
+    for placement in spanning {
+        let cell = &table.rows[placement.row].cells[placement.cell];
+        let cell_width = crate::drawing_utils::calculate_cell_width(
+            placement.col,
+            placement.colspan,
+            column_widths,
+        );
+        let lines = &text_lines[placement.row][placement.cell];
+        let required = cell_content_height(table, cell, cell_width, lines) + vertical_padding;
+        let spanned = placement.row..=placement.last_row();
+        let current: f32 = heights[spanned.clone()].iter().sum();
+        if required <= current {
+            continue;
+        }
+
+        let flexible: Vec<usize> = spanned
+            .filter(|&r| table.rows[r].height.is_none())
+            .collect();
+        if flexible.is_empty() {
+            continue;
+        }
+        let extra = (required - current) / flexible.len() as f32;
+        for r in flexible {
+            heights[r] += extra;
+        }
+    }
+
     trace!("Calculated row heights: {:?}", heights);
     Ok(heights)
 }
@@ -349,6 +716,8 @@ fn font_size_to_height(font_size: f32) -> f32 {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::font::FontMetrics;
+    use crate::style::CellStyle;
     use crate::table::{Cell, Row};
 
     #[test]
@@ -372,4 +741,230 @@ mod tests {
         assert!(layout.total_width > 0.0);
         assert!(layout.total_height > 0.0);
     }
+
+    #[test]
+    fn test_layout_caches_wrapped_lines_and_widths() {
+        let table = Table::new()
+            .with_pixel_widths(vec![60.0, 100.0])
+            .add_row(Row::new(vec![
+                Cell::new("one two three four five").with_wrap(true),
+                Cell::new("Line 1\nLine 2"),
+            ]));
+
+        let layout = calculate_layout(&table).unwrap();
+        let wrapped = layout.text_lines(0, 0);
+        let width = 60.0 - table.style.padding.left - table.style.padding.right;
+
+        assert!(wrapped.len() > 1);
+        assert!(wrapped.iter().all(|line| line.width <= width));
+        assert_eq!(
+            wrapped[0].width,
+            StandardFont::Helvetica.text_width(&wrapped[0].text, 10.0)
+        );
+        assert_eq!(
+            layout.row_heights[0],
+            wrapped.len() as f32 * 10.0 * DEFAULT_LINE_HEIGHT_MULTIPLIER
+                + table.style.padding.top
+                + table.style.padding.bottom
+        );
+        let texts: Vec<_> = layout
+            .text_lines(0, 1)
+            .iter()
+            .map(|l| l.text.as_str())
+            .collect();
+        assert_eq!(texts, vec!["Line 1", "Line 2"]);
+        assert!(layout.text_lines(1, 0).is_empty());
+    }
+
+    #[test]
+    fn test_rowspan_height_spread_across_spanned_rows() {
+        let tall = "one two three four five six seven eight nine ten eleven twelve";
+        let table = Table::new()
+            .with_pixel_widths(vec![60.0, 100.0])
+            .add_row(Row::new(vec![
+                Cell::new(tall).with_wrap(true).with_rowspan(2),
+                Cell::new("B"),
+            ]))
+            .add_row(Row::new(vec![Cell::new("C")]))
+            .add_row(Row::new(vec![Cell::new("D"), Cell::new("E")]));
+
+        let layout = calculate_layout(&table).unwrap();
+        let single = layout.row_heights[2];
+
+        assert!(layout.row_heights[0] > single);
+        assert!((layout.row_heights[0] - layout.row_heights[1]).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_auto_widths_use_standard_font_of_cell() {
+        let courier = CellStyle {
+            font_name: Some("Courier".to_string()),
+            ..Default::default()
+        };
+        let bold = CellStyle {
+            bold: true,
+            ..Default::default()
+        };
+        let table = Table::new().add_row(Row::new(vec![
+            Cell::new("Illicit"),
+            Cell::new("Illicit").with_style(courier),
+            Cell::new("Illicit").with_style(bold),
+        ]));
+
+        let layout = calculate_layout(&table).unwrap();
+        let padding = table.style.padding.left + table.style.padding.right;
+        let width = |font: StandardFont| font.text_width("Illicit", 10.0) + padding;
+        assert!((layout.column_widths[0] - width(StandardFont::Helvetica)).abs() < 0.01);
+        assert!((layout.column_widths[1] - width(StandardFont::Courier)).abs() < 0.01);
+        assert!((layout.column_widths[2] - width(StandardFont::HelveticaBold)).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_auto_layout_never_squeezes_below_longest_word() {
+        let long_text = "short words and one extraordinarily long word ".repeat(8);
+        let table = Table::new().with_total_width(360.0).add_row(Row::new(vec![
+            Cell::new(long_text.clone()).with_wrap(true),
+            Cell::new(long_text).with_wrap(true),
+            Cell::new("fixed label that does not wrap"),
+        ]));
+
+        let layout = calculate_layout(&table).unwrap();
+        let padding = table.style.padding.left + table.style.padding.right;
+        let longest_word = StandardFont::Helvetica
+            .text_width("extraordinarily", table.style.default_font_size)
+            + padding;
+        let label = StandardFont::Helvetica.text_width(
+            "fixed label that does not wrap",
+            table.style.default_font_size,
+        ) + padding;
+
+        assert!((layout.total_width - 360.0).abs() < 0.01);
+        assert!(layout.column_widths[0] >= longest_word - 0.01);
+        assert!(layout.column_widths[1] >= longest_word - 0.01);
+        assert!((layout.column_widths[2] - label).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_fit_content_widths_shrinks_between_min_and_max() {
+        let columns = [
+            ContentWidths {
+                min: 50.0,
+                max: 150.0,
+            },
+            ContentWidths {
+                min: 50.0,
+                max: 50.0,
+            },
+        ];
+
+        assert_eq!(
+            fit_content_widths(&columns, 300.0, false),
+            vec![150.0, 50.0]
+        );
+        assert_eq!(
+            fit_content_widths(&columns, 400.0, true),
+            vec![300.0, 100.0]
+        );
+        assert_eq!(
+            fit_content_widths(&columns, 150.0, false),
+            vec![100.0, 50.0]
+        );
+        assert_eq!(fit_content_widths(&columns, 80.0, false), vec![50.0, 50.0]);
+    }
+
+    #[test]
+    fn test_fraction_columns_share_leftover_space() {
+        let table = Table::new()
+            .with_total_width(400.0)
+            .with_column_widths(vec![
+                ColumnWidth::Pixels(100.0),
+                ColumnWidth::Fraction(1.0),
+                ColumnWidth::Fraction(2.0),
+            ])
+            .add_row(Row::new(vec![
+                Cell::new("A"),
+                Cell::new("B"),
+                Cell::new("C"),
+            ]));
+
+        let layout = calculate_layout(&table).unwrap();
+        assert_eq!(layout.column_widths, vec![100.0, 100.0, 200.0]);
+    }
+
+    #[test]
+    fn test_default_page_is_a4() {
+        let table = Table::new()
+            .with_column_widths(vec![
+                ColumnWidth::Percentage(50.0),
+                ColumnWidth::Fraction(1.0),
+            ])
+            .add_row(Row::new(vec![Cell::new("A"), Cell::new("B")]));
+
+        // 595pt A4 width less the default 50pt margins
+        let layout = calculate_layout(&table).unwrap();
+        assert_eq!(layout.column_widths, vec![247.5, 247.5]);
+
+        let letter = calculate_layout_for_page(&table, &PageBox::new(612.0, 792.0)).unwrap();
+        assert_eq!(letter.column_widths, vec![256.0, 256.0]);
+    }
+
+    #[test]
+    fn test_fraction_column_keeps_content_floor() {
+        let table = Table::new()
+            .with_total_width(300.0)
+            .with_column_widths(vec![ColumnWidth::Fraction(1.0), ColumnWidth::Fraction(9.0)])
+            .add_row(Row::new(vec![
+                Cell::new("a label that needs room"),
+                Cell::new("B"),
+            ]));
+
+        let layout = calculate_layout(&table).unwrap();
+        let floor = StandardFont::Helvetica
+            .text_width("a label that needs room", table.style.default_font_size)
+            + table.style.padding.left
+            + table.style.padding.right;
+        assert!((layout.column_widths[0] - floor).abs() < 0.01);
+        assert!((layout.total_width - 300.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_minmax_columns_grow_to_max_then_stop() {
+        let table = Table::new()
+            .with_total_width(400.0)
+            .with_column_widths(vec![
+                ColumnWidth::MinMax {
+                    min: 50.0,
+                    max: 120.0,
+                    preferred: Some(100.0),
+                },
+                ColumnWidth::MinMax {
+                    min: 50.0,
+                    max: 500.0,
+                    preferred: Some(100.0),
+                },
+            ])
+            .add_row(Row::new(vec![Cell::new("A"), Cell::new("B")]));
+
+        let layout = calculate_layout(&table).unwrap();
+        assert!((layout.column_widths[0] - 120.0).abs() < 0.01);
+        assert!((layout.column_widths[1] - 280.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_minmax_columns_shrink_towards_min() {
+        let table = Table::new()
+            .with_total_width(200.0)
+            .with_column_widths(vec![
+                ColumnWidth::Pixels(100.0),
+                ColumnWidth::MinMax {
+                    min: 60.0,
+                    max: 300.0,
+                    preferred: Some(200.0),
+                },
+            ])
+            .add_row(Row::new(vec![Cell::new("A"), Cell::new("B")]));
+
+        let layout = calculate_layout(&table).unwrap();
+        assert_eq!(layout.column_widths, vec![100.0, 100.0]);
+    }
 }
@@ -5,16 +5,29 @@
 
 use lopdf::content::Operation;
 use lopdf::{Document, Object, ObjectId};
+use std::collections::{BTreeSet, HashMap};
 use tracing::{debug, instrument, trace};
 
+mod bands;
 mod constants;
 mod drawing;
 mod drawing_utils;
 pub mod error;
 pub mod font;
+#[cfg(feature = "ttf-parser")]
+pub mod font_embedding;
+#[cfg(feature = "ttf-parser")]
+mod font_subset;
+mod grid;
 pub mod layout;
+pub mod page;
+pub mod page_break;
+pub mod plan;
+pub mod standard_fonts;
 pub mod style;
 pub mod table;
+#[cfg(test)]
+mod test_support;
 mod text;
 
 // Re-export constants for public use
@@ -24,10 +37,21 @@ pub use error::{Result, TableError};
 pub use font::FontMetrics;
 #[cfg(feature = "ttf-parser")]
 pub use font::TtfFontMetrics;
+#[cfg(feature = "ttf-parser")]
+pub use font_embedding::EmbeddedFont;
+pub use layout::TableLayout;
+pub use page::{
+    ContinuationPage, DecoratedPage, PageBox, PageDecorator, PageFactory, PageNumbers, Rect,
+    insert_page_after,
+};
+pub use page_break::{PageBreak, PageBreakRows, RunningSubtotals};
+pub use plan::{PagePlan, PlannedRow, TablePlan, plan_pagination, plan_pagination_with_regions};
+pub use standard_fonts::StandardFont;
 pub use style::{
-    Alignment, BorderStyle, CellStyle, Color, RowStyle, TableStyle, VerticalAlignment,
+    Alignment, BandOrder, BorderStyle, CellStyle, Color, HorizontalPagination, RowStyle,
+    TableStyle, VerticalAlignment,
 };
-pub use table::{Cell, CellImage, ColumnWidth, ImageFit, ImageOverlay, Row, Table};
+pub use table::{Cell, CellImage, ColumnWidth, ImageFit, ImageOverlay, PageFill, Row, Table};
 
 /// Optional hook for injecting tagged content around table cells.
 pub trait TaggedCellHook {
@@ -44,6 +68,43 @@ pub struct PagedTableResult {
     pub total_pages: usize,
     /// Final position after drawing (x, y on last page)
     pub final_position: (f32, f32),
+    /// Indices of rows split across a page boundary (when `TableStyle::split_rows` is enabled)
+    pub split_rows: Vec<usize>,
+    /// Area covered by the table on each page, in the order of `page_ids`
+    pub table_bounds: Vec<Rect>,
+    /// Every table row drawn, page by page and top to bottom. Header and
+    /// footer rows appear once for every page they are repeated on, and rows
+    /// split across pages once for each part.
+    pub rows: Vec<PlacedRow>,
+    /// Every table cell drawn, in the order of `rows`. Cells spanning rows
+    /// cover all of them.
+    pub cells: Vec<PlacedCell>,
+}
+
+/// A table row, or the part of a split row, as drawn on a page
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PlacedRow {
+    pub page_id: ObjectId,
+    /// Index of the row in the table
+    pub row: usize,
+    /// Area of the row in the visible page coordinates
+    pub rect: Rect,
+    /// Whether this part of the row continues from the previous page
+    pub continued: bool,
+}
+
+/// A table cell, or the part of a cell in a split row, as drawn on a page
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PlacedCell {
+    pub page_id: ObjectId,
+    /// Row the cell is declared in
+    pub row: usize,
+    /// Grid column the cell starts at
+    pub column: usize,
+    /// Area of the cell in the visible page coordinates
+    pub rect: Rect,
+    /// Whether this part of the cell continues from the previous page
+    pub continued: bool,
 }
 
 /// Extension trait for lopdf::Document to add table drawing capabilities
@@ -59,6 +120,19 @@ pub trait TableDrawing {
     /// Returns Ok(()) on success, or an error if the table cannot be drawn
     fn draw_table(&mut self, page_id: ObjectId, table: Table, position: (f32, f32)) -> Result<()>;
 
+    /// Draw a table at the specified position on a page, like
+    /// [`draw_table`](Self::draw_table)
+    ///
+    /// # Returns
+    /// Returns a PagedTableResult with the single page and the placement of
+    /// every row and cell, or an error if the table cannot be drawn
+    fn draw_table_with_placements(
+        &mut self,
+        page_id: ObjectId,
+        table: Table,
+        position: (f32, f32),
+    ) -> Result<PagedTableResult>;
+
     /// Add a table to a page with automatic positioning
     ///
     /// This method will find an appropriate position on the page for the table
@@ -66,7 +140,8 @@ pub trait TableDrawing {
 
     /// Create table content operations without adding to document
     ///
-    /// Useful for custom positioning or combining with other content
+    /// Useful for custom positioning or combining with other content. The table
+    /// is laid out and aligned on an A4 page.
     fn create_table_content(&self, table: &Table, position: (f32, f32)) -> Result<Vec<Object>>;
 
     /// Draw a table with automatic page wrapping
@@ -89,6 +164,46 @@ pub trait TableDrawing {
         position: (f32, f32),
     ) -> Result<PagedTableResult>;
 
+    /// Draw a table at the specified position on a page with a layout computed
+    /// beforehand, e.g. by [`layout::calculate_layout_for_page`].
+    ///
+    /// The table is borrowed, so the same table and layout can be drawn many
+    /// times without laying it out again. The layout must have been computed
+    /// for this table: drawing fails with [`TableError::LayoutError`] when
+    /// the table's rows, cell text or styles have changed since.
+    fn draw_table_with_layout(
+        &mut self,
+        page_id: ObjectId,
+        table: &Table,
+        layout: &TableLayout,
+        position: (f32, f32),
+    ) -> Result<PagedTableResult>;
+
+    /// Draw a table with automatic page wrapping with a layout computed
+    /// beforehand (see [`draw_table_with_layout`](Self::draw_table_with_layout))
+    fn draw_table_with_pagination_and_layout(
+        &mut self,
+        page_id: ObjectId,
+        table: &Table,
+        layout: &TableLayout,
+        position: (f32, f32),
+    ) -> Result<PagedTableResult>;
+
+    /// Draw a table on the pages of a plan from [`plan_pagination`] (or
+    /// [`plan_pagination_with_regions`]), starting on `page_id` and creating
+    /// the continuation pages the plan needs.
+    ///
+    /// Fails with [`TableError::LayoutError`] when a page does not match the
+    /// region it was planned for. Tables split into column bands have no
+    /// plan (see [`TableError::BandedTablePlan`]); draw them with
+    /// [`draw_table_with_pagination`](Self::draw_table_with_pagination).
+    fn draw_table_plan(
+        &mut self,
+        page_id: ObjectId,
+        table: &Table,
+        plan: &TablePlan,
+    ) -> Result<PagedTableResult>;
+
     /// Draw a table with an optional tagged-cell hook.
     ///
     /// Existing rendering behavior is unchanged when `hook` is `None`.
@@ -113,39 +228,34 @@ pub trait TableDrawing {
 }
 
 impl TableDrawing for Document {
-    #[instrument(skip(self, table), fields(table_rows = table.rows.len()))]
     fn draw_table(&mut self, page_id: ObjectId, table: Table, position: (f32, f32)) -> Result<()> {
+        self.draw_table_with_placements(page_id, table, position)
+            .map(|_| ())
+    }
+
+    #[instrument(skip(self, table), fields(table_rows = table.rows.len()))]
+    fn draw_table_with_placements(
+        &mut self,
+        page_id: ObjectId,
+        table: Table,
+        position: (f32, f32),
+    ) -> Result<PagedTableResult> {
         debug!("Drawing table at position {:?}", position);
 
-        let layout = layout::calculate_layout(&table)?;
+        let page = PageBox::of_page(self, page_id)?.visible();
+        let layout = layout::calculate_layout_for_page(&table, &page)?;
         trace!("Calculated layout: {:?}", layout);
 
-        let image_reg = if drawing::table_has_images(&table) {
-            Some(drawing::register_all_images(self, &table))
-        } else {
-            None
-        };
-
-        let operations = drawing::generate_table_operations(
-            &table,
-            &layout,
-            position,
-            None,
-            image_reg.as_ref(),
-        )?;
-
-        if let Some(ref reg) = image_reg {
-            reg.register_on_page(self, page_id)?;
-        }
-
-        drawing::add_operations_to_page(self, page_id, operations)?;
-
-        Ok(())
+        draw_on_page(self, page_id, &table, &layout, position, None)
     }
 
     #[instrument(skip(self, table))]
     fn add_table_to_page(&mut self, page_id: ObjectId, table: Table) -> Result<()> {
-        let position = (DEFAULT_MARGIN, A4_HEIGHT - DEFAULT_MARGIN - 50.0);
+        let page = PageBox::of_page(self, page_id)?.visible();
+        let position = (
+            page.left + table.style.left_margin,
+            page.top - table.style.top_margin - 50.0,
+        );
         self.draw_table(page_id, table, position)
     }
 
@@ -156,6 +266,10 @@ impl TableDrawing for Document {
             ));
         }
         let layout = layout::calculate_layout(table)?;
+        let position = (
+            PageBox::default().table_x(&table.style, layout.total_width, position.0),
+            position.1,
+        );
         drawing::generate_table_operations(table, &layout, position, None, None)
     }
 
@@ -168,27 +282,62 @@ impl TableDrawing for Document {
     ) -> Result<PagedTableResult> {
         debug!("Drawing paginated table at position {:?}", position);
 
-        let layout = layout::calculate_layout(&table)?;
+        let page = PageBox::of_page(self, page_id)?.visible();
+        let layout = layout::calculate_layout_for_page(&table, &page)?;
         trace!("Calculated layout: {:?}", layout);
 
-        let image_reg = if drawing::table_has_images(&table) {
-            let reg = drawing::register_all_images(self, &table);
-            reg.register_on_page(self, page_id)?;
-            Some(reg)
+        draw_paginated(self, page_id, &table, &layout, position, None)
+    }
+
+    #[instrument(skip(self, table, layout), fields(table_rows = table.rows.len()))]
+    fn draw_table_with_layout(
+        &mut self,
+        page_id: ObjectId,
+        table: &Table,
+        layout: &TableLayout,
+        position: (f32, f32),
+    ) -> Result<PagedTableResult> {
+        debug!("Drawing table with precomputed layout at {:?}", position);
+        layout.check_table(table)?;
+        draw_on_page(self, page_id, table, layout, position, None)
+    }
+
+    #[instrument(skip(self, table, layout), fields(table_rows = table.rows.len()))]
+    fn draw_table_with_pagination_and_layout(
+        &mut self,
+        page_id: ObjectId,
+        table: &Table,
+        layout: &TableLayout,
+        position: (f32, f32),
+    ) -> Result<PagedTableResult> {
+        debug!(
+            "Drawing paginated table with precomputed layout at {:?}",
+            position
+        );
+        layout.check_table(table)?;
+        draw_paginated(self, page_id, table, layout, position, None)
+    }
+
+    fn draw_table_plan(
+        &mut self,
+        page_id: ObjectId,
+        table: &Table,
+        plan: &TablePlan,
+    ) -> Result<PagedTableResult> {
+        debug!("Drawing planned table on {} pages", plan.total_pages());
+        plan.layout().check_table(table)?;
+
+        // Registered on the pages drawn on, which exclude the start page when
+        // the table moves to a new page
+        let image_reg = if drawing::table_has_images(table) {
+            Some(drawing::register_all_images(self, table))
         } else {
             None
         };
 
-        let result = drawing::draw_table_paginated(
-            self,
-            page_id,
-            &table,
-            &layout,
-            position,
-            None,
-            image_reg.as_ref(),
-        )?;
-
+        let result =
+            drawing::draw_table_plan(self, page_id, table, plan, None, image_reg.as_ref())?;
+        register_resources(self, table, image_reg.as_ref(), &result)?;
         Ok(result)
     }
 
@@ -200,28 +349,10 @@ impl TableDrawing for Document {
         hook: Option<&mut dyn TaggedCellHook>,
     ) -> Result<()> {
         debug!("Drawing table with hook at position {:?}", position);
-        let layout = layout::calculate_layout(&table)?;
-
-        let image_reg = if drawing::table_has_images(&table) {
-            Some(drawing::register_all_images(self, &table))
-        } else {
-            None
-        };
+        let page = PageBox::of_page(self, page_id)?.visible();
+        let layout = layout::calculate_layout_for_page(&table, &page)?;
 
-        let operations = drawing::generate_table_operations(
-            &table,
-            &layout,
-            position,
-            hook,
-            image_reg.as_ref(),
-        )?;
-
-        if let Some(ref reg) = image_reg {
-            reg.register_on_page(self, page_id)?;
-        }
-
-        drawing::add_operations_to_page(self, page_id, operations)?;
-        Ok(())
+        draw_on_page(self, page_id, &table, &layout, position, hook).map(|_| ())
     }
 
     fn draw_table_with_pagination_and_hook(
@@ -235,32 +366,143 @@ impl TableDrawing for Document {
             "Drawing paginated table with hook at position {:?}",
             position
         );
-        let layout = layout::calculate_layout(&table)?;
+        let page = PageBox::of_page(self, page_id)?.visible();
+        let layout = layout::calculate_layout_for_page(&table, &page)?;
 
-        let image_reg = if drawing::table_has_images(&table) {
-            let reg = drawing::register_all_images(self, &table);
-            reg.register_on_page(self, page_id)?;
-            Some(reg)
-        } else {
-            None
-        };
+        draw_paginated(self, page_id, &table, &layout, position, hook)
+    }
+}
 
-        drawing::draw_table_paginated(
-            self,
-            page_id,
-            &table,
-            &layout,
-            position,
-            hook,
-            image_reg.as_ref(),
-        )
+/// Draw a laid out table on one page, aligned following its style
+fn draw_on_page(
+    doc: &mut Document,
+    page_id: ObjectId,
+    table: &Table,
+    layout: &TableLayout,
+    position: (f32, f32),
+    hook: Option<&mut dyn TaggedCellHook>,
+) -> Result<PagedTableResult> {
+    let page = PageBox::of_page(doc, page_id)?.visible();
+    let position = (
+        page.table_x(&table.style, layout.total_width, position.0),
+        position.1,
+    );
+
+    let image_reg = if drawing::table_has_images(table) {
+        Some(drawing::register_all_images(doc, table))
+    } else {
+        None
+    };
+
+    let operations =
+        drawing::generate_table_operations(table, layout, position, hook, image_reg.as_ref())?;
+    drawing::add_operations_to_page(doc, page_id, operations)?;
+
+    let result = drawing::single_page_result(page_id, layout, position);
+    register_resources(doc, table, image_reg.as_ref(), &result)?;
+    Ok(result)
+}
+
+/// Draw a laid out table from a page, continuing on new pages as needed
+fn draw_paginated(
+    doc: &mut Document,
+    page_id: ObjectId,
+    table: &Table,
+    layout: &TableLayout,
+    position: (f32, f32),
+    hook: Option<&mut dyn TaggedCellHook>,
+) -> Result<PagedTableResult> {
+    // Registered on the pages the table is drawn on
+    let image_reg = if drawing::table_has_images(table) {
+        Some(drawing::register_all_images(doc, table))
+    } else {
+        None
+    };
+
+    let result = drawing::draw_table_paginated(
+        doc,
+        page_id,
+        table,
+        layout,
+        position,
+        hook,
+        image_reg.as_ref(),
+    )?;
+    register_resources(doc, table, image_reg.as_ref(), &result)?;
+    Ok(result)
+}
+
+/// Add the images and fonts the table draws with to the resources of the
+/// pages it was drawn on, and only to those
+fn register_resources(
+    doc: &mut Document,
+    table: &Table,
+    image_reg: Option<&drawing::ImageXObjects>,
+    result: &PagedTableResult,
+) -> Result<()> {
+    let mut references = page::ResourceReferences::new(doc);
+    if let Some(reg) = image_reg {
+        register_images(doc, &mut references, reg, result)?;
+    }
+    register_fonts(doc, &mut references, table, result)
+}
+
+/// Add the table's images, and the ExtGState of their overlays, to the
+/// resources of every page it was drawn on
+fn register_images(
+    doc: &mut Document,
+    references: &mut page::ResourceReferences,
+    reg: &drawing::ImageXObjects,
+    result: &PagedTableResult,
+) -> Result<()> {
+    for &page_id in &result.page_ids {
+        reg.register_on_page(doc, references, page_id)?;
+    }
+    Ok(())
+}
+
+/// Add the fonts the table draws with to the resources of every page it was
+/// drawn on: the standard fonts a page lacks, and the table's embedded fonts.
+/// Subset fonts are rewritten with the glyphs drawn so far.
+fn register_fonts(
+    doc: &mut Document,
+    references: &mut page::ResourceReferences,
+    table: &Table,
+    result: &PagedTableResult,
+) -> Result<()> {
+    let standard_fonts = drawing::standard_fonts_used(table);
+    let mut standard_font_ids = HashMap::new();
+    for &page_id in &result.page_ids {
+        // Fonts already on the page under a standard font's name are kept
+        let page_fonts: BTreeSet<Vec<u8>> = doc.get_page_fonts(page_id)?.into_keys().collect();
+        for &font in &standard_fonts {
+            let name = font.resource_name();
+            if page_fonts.contains(name.as_bytes()) {
+                continue;
+            }
+            let font_id = *standard_font_ids
+                .entry(font)
+                .or_insert_with(|| doc.add_object(font.font_dictionary()));
+            page::add_font_resource(doc, references, page_id, name, font_id)?;
+        }
+        for (name, font_id) in &table.font_resources {
+            page::add_font_resource(doc, references, page_id, name, *font_id)?;
+        }
+    }
+    #[cfg(feature = "ttf-parser")]
+    for font in &table.embedded_fonts {
+        font.write_subset(doc)?;
     }
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_support::*;
+
     use lopdf::content::{Content, Operation};
+
     use lopdf::{Document, Object, dictionary};
 
     #[test]
@@ -373,24 +615,6 @@ mod tests {
         names
     }
 
-    #[derive(Debug, Clone, Copy)]
-    struct RectExtents {
-        max_top: f32,
-        min_bottom: f32,
-    }
-
-    fn object_to_f32(object: &Object) -> Option<f32> {
-        match object {
-            Object::Integer(v) => Some(*v as f32),
-            Object::Real(v) => Some(*v),
-            _ => None,
-        }
-    }
-
-    fn approx_eq(a: f32, b: f32) -> bool {
-        (a - b).abs() <= 0.001
-    }
-
     fn op_has_rgb(op: &Operation, operator: &str, color: Color) -> bool {
         op.operator == operator
             && op.operands.len() == 3
@@ -410,43 +634,6 @@ mod tests {
             && operations.iter().any(|op| op_has_line_width(op, width))
     }
 
-    fn page_content_operations(doc: &Document, page_id: ObjectId) -> Vec<Operation> {
-        let bytes = doc
-            .get_page_content(page_id)
-            .expect("page content should be readable");
-        Content::decode(&bytes)
-            .expect("page content should decode")
-            .operations
-    }
-
-    fn page_rect_extents(doc: &Document, page_id: ObjectId) -> Option<RectExtents> {
-        let bytes = doc.get_page_content(page_id).ok()?;
-        let content = Content::decode(&bytes).ok()?;
-        let mut max_top = f32::NEG_INFINITY;
-        let mut min_bottom = f32::INFINITY;
-        let mut found = false;
-
-        for op in content.operations {
-            if op.operator != "re" || op.operands.len() != 4 {
-                continue;
-            }
-            let y = object_to_f32(&op.operands[1])?;
-            let h = object_to_f32(&op.operands[3])?;
-            max_top = max_top.max(y + h);
-            min_bottom = min_bottom.min(y);
-            found = true;
-        }
-
-        if found {
-            Some(RectExtents {
-                max_top,
-                min_bottom,
-            })
-        } else {
-            None
-        }
-    }
-
     #[test]
     fn test_cell_border_overrides_emit_custom_stroke_ops() {
         let custom_color = Color::rgb(0.11, 0.22, 0.33);
@@ -571,6 +758,72 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_fonts_registered_only_on_table_pages() {
+        let (mut doc, page_id) = make_test_doc();
+        let resources = doc
+            .get_dictionary(page_id)
+            .unwrap()
+            .get(b"Resources")
+            .unwrap()
+            .clone();
+        let other_page = insert_page_after(
+            &mut doc,
+            page_id,
+            dictionary! {
+                "Type" => "Page",
+                "MediaBox" => vec![0.into(), 0.into(), 595.into(), 842.into()],
+                "Resources" => resources,
+            },
+        )
+        .unwrap();
+        let mut table = numbered_rows_table(50);
+        table.style.font_name = "Courier".to_string();
+
+        let result = doc
+            .draw_table_with_pagination(page_id, table, (50.0, 792.0))
+            .unwrap();
+
+        assert_eq!(result.total_pages, 3);
+        assert!(!result.page_ids.contains(&other_page));
+        for &page in &result.page_ids {
+            assert!(
+                doc.get_page_fonts(page)
+                    .unwrap()
+                    .contains_key(b"F2".as_slice())
+            );
+        }
+        let other_fonts = doc.get_page_fonts(other_page).unwrap();
+        assert_eq!(
+            other_fonts.into_keys().collect::<Vec<_>>(),
+            vec![b"F1".to_vec()]
+        );
+    }
+
+    #[test]
+    fn test_standard_font_text_uses_win_ansi_encoding() {
+        let style = TableStyle {
+            font_name: "Times-Roman".to_string(),
+            ..Default::default()
+        };
+        let table = Table::new()
+            .with_style(style)
+            .add_row(Row::new(vec![Cell::new("café").bold(), Cell::new("€5")]));
+
+        let ops = Document::with_version("1.5")
+            .create_table_content(&table, (50.0, 750.0))
+            .expect("table content should be generated");
+        assert_eq!(extract_tf_font_names(&ops), vec!["F3-Bold", "F3"]);
+        let strings: Vec<&[u8]> = ops
+            .iter()
+            .filter_map(|object| match object {
+                Object::String(bytes, lopdf::StringFormat::Literal) => Some(bytes.as_slice()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(strings, vec![b"caf\xe9".as_slice(), b"\x805".as_slice()]);
+    }
+
     #[test]
     fn test_embedded_regular_resource_used_as_bold_fallback() {
         let mut style = TableStyle::default();
@@ -627,6 +880,60 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_italic_cells_use_italic_fonts() {
+        let mut style = TableStyle {
+            font_name: "Times-Roman".to_string(),
+            ..Default::default()
+        };
+        let table = Table::new()
+            .with_style(style.clone())
+            .add_row(Row::new(vec![
+                Cell::new("a").italic(),
+                Cell::new("b").bold().italic(),
+                Cell::new("c").with_style(CellStyle {
+                    font_name: Some("Courier".to_string()),
+                    italic: true,
+                    ..Default::default()
+                }),
+            ]));
+        let ops = Document::with_version("1.5")
+            .create_table_content(&table, (50.0, 750.0))
+            .expect("table content should be generated");
+        assert_eq!(
+            extract_tf_font_names(&ops),
+            vec!["F3-Italic", "F3-BoldItalic", "F2-Italic"]
+        );
+
+        // Embedded fonts: bold italic falls back to the bold font
+        style.embedded_font_resource_name = Some("EF0".to_string());
+        style.embedded_font_resource_name_bold = Some("EF0B".to_string());
+        style.embedded_font_resource_name_italic = Some("EF0I".to_string());
+        let table = Table::new()
+            .with_style(style)
+            .add_row(Row::new(vec![
+                Cell::new("a").italic(),
+                Cell::new("b").bold().italic(),
+            ]))
+            .with_font_metrics(MockMetrics {
+                char_width_pts: 5.0,
+            })
+            .with_bold_font_metrics(MockMetrics {
+                char_width_pts: 6.0,
+            })
+            .with_italic_font_metrics(MockMetrics {
+                char_width_pts: 7.0,
+            });
+        let ops = Document::with_version("1.5")
+            .create_table_content(&table, (50.0, 750.0))
+            .expect("table content should be generated");
+        assert_eq!(extract_tf_font_names(&ops), vec!["EF0I", "EF0B"]);
+
+        let layout = layout::calculate_layout(&table).unwrap();
+        assert_eq!(layout.text_lines(0, 0)[0].width, 7.0);
+        assert_eq!(layout.text_lines(0, 1)[0].width, 6.0);
+    }
+
     #[test]
     fn test_tagged_cell_hook_is_invoked() {
         struct Hook {
@@ -1081,41 +1388,391 @@ mod tests {
         buf.into_inner()
     }
 
-    fn make_test_doc() -> (Document, ObjectId) {
-        let mut doc = Document::with_version("1.7");
-        let pages_id = doc.add_object(dictionary! {
-            "Type" => "Pages",
-            "Kids" => vec![],
-            "Count" => 0,
-        });
-        let page_id = doc.add_object(dictionary! {
-            "Type" => "Page",
-            "Parent" => pages_id,
-            "MediaBox" => vec![0.into(), 0.into(), 595.into(), 842.into()],
-        });
-        if let Ok(Object::Dictionary(pages)) = doc.get_object_mut(pages_id) {
-            if let Ok(Object::Array(kids)) = pages.get_mut(b"Kids") {
-                kids.push(page_id.into());
-            }
-            pages.set("Count", Object::Integer(1));
+    #[test]
+    fn test_rowspan_cell_background_covers_spanned_rows() {
+        let bg_color = Color::rgb(0.31, 0.42, 0.53);
+        let style = CellStyle {
+            background_color: Some(bg_color),
+            ..Default::default()
+        };
+
+        let table = Table::new()
+            .with_pixel_widths(vec![100.0, 100.0])
+            .add_row(
+                Row::new(vec![
+                    Cell::new("Merged").with_style(style).with_rowspan(2),
+                    Cell::new("B"),
+                ])
+                .with_height(20.0),
+            )
+            .add_row(Row::new(vec![Cell::new("C")]).with_height(30.0));
+
+        let objects = Document::with_version("1.7")
+            .create_table_content(&table, (50.0, 750.0))
+            .expect("table content should be generated");
+        let operations = crate::drawing_utils::objects_to_operations(&objects);
+
+        let fill_idx = operations
+            .iter()
+            .position(|op| op_has_rgb(op, "rg", bg_color))
+            .expect("expected merged cell background fill");
+        let rect = &operations[fill_idx + 1];
+        assert_eq!(rect.operator, "re");
+        let height = object_to_f32(&rect.operands[3]).unwrap();
+        assert!(
+            approx_eq(height, 50.0),
+            "expected merged background height 50, got {height}"
+        );
+    }
+
+    #[test]
+    fn test_rowspan_suppresses_interior_horizontal_border() {
+        let table = Table::new()
+            .with_pixel_widths(vec![100.0, 100.0])
+            .add_row(
+                Row::new(vec![Cell::new("Merged").with_rowspan(2), Cell::new("B")])
+                    .with_height(20.0),
+            )
+            .add_row(Row::new(vec![Cell::new("C")]).with_height(20.0));
+
+        let objects = Document::with_version("1.7")
+            .create_table_content(&table, (50.0, 750.0))
+            .expect("table content should be generated");
+        let operations = crate::drawing_utils::objects_to_operations(&objects);
+
+        // The only horizontal line at the row boundary (y = 730) must start at the second column
+        let on_boundary =
+            |op: &Operation| object_to_f32(&op.operands[1]).is_some_and(|y| approx_eq(y, 730.0));
+        let boundary_starts: Vec<f32> = operations
+            .windows(2)
+            .filter(|pair| {
+                pair[0].operator == "m"
+                    && pair[1].operator == "l"
+                    && on_boundary(&pair[0])
+                    && on_boundary(&pair[1])
+            })
+            .filter_map(|pair| object_to_f32(&pair[0].operands[0]))
+            .collect();
+        assert_eq!(boundary_starts.len(), 1);
+        assert!(approx_eq(boundary_starts[0], 150.0));
+    }
+
+    #[test]
+    fn test_paginated_rowspan_group_kept_on_one_page() {
+        let (mut doc, page_id) = make_test_doc();
+
+        let style = TableStyle {
+            page_height: Some(842.0),
+            top_margin: 50.0,
+            bottom_margin: 50.0,
+            ..Default::default()
+        };
+
+        // First page fits 3 rows of 30pt between y=150 and the bottom margin
+        let mut table = Table::new()
+            .with_style(style)
+            .with_pixel_widths(vec![100.0, 100.0]);
+        for row in 0..2 {
+            table = table.add_row(
+                Row::new(vec![Cell::new(format!("r{row}")), Cell::new("x")]).with_height(30.0),
+            );
         }
-        let font_id = doc.add_object(dictionary! {
-            "Type" => "Font",
-            "Subtype" => "Type1",
-            "BaseFont" => "Helvetica",
-        });
-        let resources_id = doc.add_object(dictionary! {
-            "Font" => dictionary! { "F1" => font_id },
-        });
-        if let Ok(Object::Dictionary(page)) = doc.get_object_mut(page_id) {
-            page.set("Resources", resources_id);
+        table = table
+            .add_row(
+                Row::new(vec![Cell::new("span").with_rowspan(2), Cell::new("y")]).with_height(30.0),
+            )
+            .add_row(Row::new(vec![Cell::new("z")]).with_height(30.0));
+
+        let result = doc
+            .draw_table_with_pagination(page_id, table, (50.0, 150.0))
+            .expect("paginated table draw should succeed");
+
+        assert_eq!(result.page_ids.len(), 2);
+        let second_page =
+            page_rect_extents(&doc, result.page_ids[1]).expect("second page should have rects");
+        assert!(
+            approx_eq(second_page.max_top - second_page.min_bottom, 60.0),
+            "expected both spanned rows on the continuation page"
+        );
+    }
+
+    #[test]
+    fn test_split_rows_carries_wrapped_lines_onto_continuation_page() {
+        const BOTTOM_MARGIN: f32 = 50.0;
+        let (mut doc, page_id) = make_test_doc();
+
+        let style = TableStyle {
+            page_height: Some(842.0),
+            top_margin: 50.0,
+            bottom_margin: BOTTOM_MARGIN,
+            split_rows: true,
+            ..Default::default()
+        };
+
+        // 120 short words wrap to one word per line: far taller than a page
+        let long_text = vec!["word"; 120].join(" ");
+        let table = Table::new()
+            .with_style(style)
+            .with_header_rows(1)
+            .with_pixel_widths(vec![40.0, 100.0])
+            .add_row(Row::new(vec![Cell::new("H1"), Cell::new("H2")]))
+            .add_row(Row::new(vec![
+                Cell::new(long_text).with_wrap(true),
+                Cell::new("short"),
+            ]));
+
+        let result = doc
+            .draw_table_with_pagination(page_id, table, (50.0, 400.0))
+            .expect("paginated table draw should succeed");
+
+        assert!(result.page_ids.len() >= 3, "expected the row to span pages");
+        assert_eq!(result.split_rows, vec![1]);
+
+        let mut word_lines = 0;
+        for &page in &result.page_ids {
+            let extents = page_rect_extents(&doc, page).expect("page should have rects");
+            assert!(extents.min_bottom >= BOTTOM_MARGIN - 0.01);
+
+            let ops = page_content_operations(&doc, page);
+            word_lines += ops
+                .iter()
+                .filter(|op| {
+                    op.operator == "Tj"
+                        && op.operands.first().and_then(|o| o.as_str().ok()) == Some(b"word")
+                })
+                .count();
         }
-        let catalog_id = doc.add_object(dictionary! {
-            "Type" => "Catalog",
-            "Pages" => pages_id,
-        });
-        doc.trailer.set("Root", catalog_id);
-        (doc, page_id)
+        assert_eq!(word_lines, 120, "every wrapped line should be drawn once");
+    }
+
+    #[test]
+    fn test_split_row_tail_keeps_cell_padding() {
+        let (mut doc, page_id) = make_test_doc();
+        let style = TableStyle {
+            page_height: Some(842.0),
+            split_rows: true,
+            ..Default::default()
+        };
+        let padded = CellStyle {
+            padding: Some(style::Padding::uniform(20.0)),
+            ..Default::default()
+        };
+        let table = Table::new()
+            .with_style(style)
+            .with_pixel_widths(vec![80.0])
+            .add_row(Row::new(vec![
+                Cell::new(vec!["word"; 40].join(" "))
+                    .with_wrap(true)
+                    .with_style(padded),
+            ]));
+
+        let result = doc
+            .draw_table_with_pagination(page_id, table, (50.0, 300.0))
+            .expect("paginated table draw should succeed");
+
+        assert_eq!(result.split_rows, vec![0]);
+        let tail = result.rows.last().unwrap();
+        assert!(tail.continued);
+        let tail_lines = page_texts(&doc, tail.page_id).len() as f32;
+        let line_height = 10.0 * DEFAULT_LINE_HEIGHT_MULTIPLIER;
+        assert!(approx_eq(tail.rect.height, tail_lines * line_height + 40.0));
+    }
+
+    #[test]
+    fn test_rows_move_whole_when_split_rows_disabled() {
+        let (mut doc, page_id) = make_test_doc();
+
+        let long_text = vec!["word"; 30].join(" ");
+        let table = Table::new()
+            .with_pixel_widths(vec![40.0])
+            .add_row(Row::new(vec![Cell::new("first")]))
+            .add_row(Row::new(vec![Cell::new(long_text).with_wrap(true)]));
+
+        let result = doc
+            .draw_table_with_pagination(page_id, table, (50.0, 200.0))
+            .expect("paginated table draw should succeed");
+
+        assert_eq!(result.page_ids.len(), 2);
+        assert!(result.split_rows.is_empty());
+    }
+
+    #[test]
+    fn test_paginated_footer_rows_repeat_and_final_footer_closes_table() {
+        const BOTTOM_MARGIN: f32 = 50.0;
+        let (mut doc, page_id) = make_test_doc();
+
+        let style = TableStyle {
+            page_height: Some(842.0),
+            top_margin: 50.0,
+            bottom_margin: BOTTOM_MARGIN,
+            ..Default::default()
+        };
+
+        let mut table = Table::new()
+            .with_style(style)
+            .with_header_rows(1)
+            .with_footer_rows(1)
+            .with_final_footer_rows(1)
+            .with_pixel_widths(vec![200.0])
+            .add_row(Row::new(vec![Cell::new("Header")]).with_height(30.0));
+        for row in 0..60 {
+            table =
+                table.add_row(Row::new(vec![Cell::new(format!("row-{row}"))]).with_height(30.0));
+        }
+        table = table
+            .add_row(Row::new(vec![Cell::new("Grand total")]).with_height(30.0))
+            .add_row(Row::new(vec![Cell::new("Legend")]).with_height(30.0));
+
+        let result = doc
+            .draw_table_with_pagination(page_id, table, (50.0, 500.0))
+            .expect("paginated table draw should succeed");
+
+        assert!(result.page_ids.len() >= 3);
+        let last = result.page_ids.len() - 1;
+        for (idx, &page) in result.page_ids.iter().enumerate() {
+            assert!(
+                page_shows_text(&doc, page, "Legend"),
+                "footer missing on page {idx}"
+            );
+            assert_eq!(page_shows_text(&doc, page, "Grand total"), idx == last);
+            let extents = page_rect_extents(&doc, page).expect("page should have rects");
+            assert!(extents.min_bottom >= BOTTOM_MARGIN - 0.01);
+        }
+    }
+
+    #[test]
+    fn test_footer_rows_exceeding_table_are_rejected() {
+        let table = Table::new()
+            .with_footer_rows(2)
+            .add_row(Row::new(vec![Cell::new("only")]));
+        assert!(table.validate().is_err());
+    }
+
+    #[test]
+    fn test_keep_together_group_moves_to_next_page() {
+        let (mut doc, page_id) = make_test_doc();
+        // 24 rows of 30pt fit between y=792 and the 50pt bottom margin
+        let table = numbered_rows_table(40).with_keep_together(22..26);
+
+        let result = doc
+            .draw_table_with_pagination(page_id, table, (50.0, 792.0))
+            .expect("paginated table draw should succeed");
+
+        let first = page_texts(&doc, result.page_ids[0]);
+        assert_eq!(first.last().map(String::as_str), Some("item-21"));
+        let second = page_texts(&doc, result.page_ids[1]);
+        assert_eq!(second[0], "item-22");
+    }
+
+    #[test]
+    fn test_keep_with_next_row_moves_with_following_row() {
+        let (mut doc, page_id) = make_test_doc();
+        let mut table = numbered_rows_table(40);
+        table.rows[23] = table.rows[23].clone().with_keep_with_next(true);
+
+        let result = doc
+            .draw_table_with_pagination(page_id, table, (50.0, 792.0))
+            .expect("paginated table draw should succeed");
+
+        let first = page_texts(&doc, result.page_ids[0]);
+        assert_eq!(first.last().map(String::as_str), Some("item-22"));
+        let second = page_texts(&doc, result.page_ids[1]);
+        assert_eq!(second[0], "item-23");
+    }
+
+    #[test]
+    fn test_header_rows_are_not_orphaned_at_page_bottom() {
+        let (mut doc, page_id) = make_test_doc();
+        let mut table = numbered_rows_table(10);
+        table
+            .rows
+            .insert(0, Row::new(vec![Cell::new("Header")]).with_height(30.0));
+        table.header_rows = 1;
+
+        // Room for the header row alone above the bottom margin
+        let result = doc
+            .draw_table_with_pagination(page_id, table, (50.0, 100.0))
+            .expect("paginated table draw should succeed");
+
+        assert_eq!(result.total_pages, 1);
+        assert_ne!(result.page_ids[0], page_id);
+        assert!(page_texts(&doc, page_id).is_empty());
+        let texts = page_texts(&doc, result.page_ids[0]);
+        assert_eq!(texts[..2], ["Header", "item-0"]);
+    }
+
+    #[test]
+    fn test_max_rows_per_page_repeats_headers() {
+        let (mut doc, page_id) = make_test_doc();
+        let table = with_header_row(numbered_rows_table(12)).with_max_rows_per_page(5);
+
+        let result = doc
+            .draw_table_with_pagination(page_id, table, (50.0, 792.0))
+            .expect("paginated table draw should succeed");
+
+        assert_eq!(result.total_pages, 3);
+        let counts: Vec<usize> = result
+            .page_ids
+            .iter()
+            .map(|&page| {
+                let texts = page_texts(&doc, page);
+                assert_eq!(texts[0], "Header");
+                texts.iter().filter(|t| t.starts_with("item-")).count()
+            })
+            .collect();
+        assert_eq!(counts, vec![5, 5, 2]);
+    }
+
+    #[test]
+    fn test_page_break_before_row_starts_new_page() {
+        let (mut doc, page_id) = make_test_doc();
+        let mut table = with_header_row(numbered_rows_table(6));
+        table.rows[4] = table.rows[4].clone().with_page_break_before(true);
+        // A keep-with-next row does not pull a forced page break back
+        table.rows[3] = table.rows[3].clone().with_keep_with_next(true);
+
+        let result = doc
+            .draw_table_with_pagination(page_id, table, (50.0, 792.0))
+            .expect("paginated table draw should succeed");
+
+        assert_eq!(result.total_pages, 2);
+        let first = page_texts(&doc, result.page_ids[0]);
+        assert_eq!(first.last().map(String::as_str), Some("item-2"));
+        let second = page_texts(&doc, result.page_ids[1]);
+        assert_eq!(second[..2], ["Header", "item-3"]);
+    }
+
+    #[test]
+    fn test_page_fill_adds_filler_rows_to_bottom_margin() {
+        let (mut doc, page_id) = make_test_doc();
+        let template = Row::new(vec![Cell::empty()]).with_height(30.0);
+        let table = numbered_rows_table(5).with_page_fill(PageFill::FillerRows(template));
+
+        let result = doc
+            .draw_table_with_pagination(page_id, table, (50.0, 792.0))
+            .expect("paginated table draw should succeed");
+
+        // 592pt remain below the data rows: 19 filler rows of 30pt
+        assert_eq!(result.total_pages, 1);
+        assert!(approx_eq(result.final_position.1, 792.0 - 24.0 * 30.0));
+        let extents = page_rect_extents(&doc, page_id).expect("page should have rects");
+        assert!(approx_eq(extents.min_bottom, result.final_position.1));
+        assert_eq!(page_texts(&doc, page_id).len(), 5);
+    }
+
+    #[test]
+    fn test_page_fill_stretches_last_row_to_bottom_margin() {
+        let (mut doc, page_id) = make_test_doc();
+        let table = numbered_rows_table(30).with_page_fill(PageFill::StretchLastRow);
+
+        let result = doc
+            .draw_table_with_pagination(page_id, table, (50.0, 792.0))
+            .expect("paginated table draw should succeed");
+
+        assert_eq!(result.total_pages, 2);
+        assert!(approx_eq(result.final_position.1, 50.0));
+        let extents = page_rect_extents(&doc, result.page_ids[1]).expect("page should have rects");
+        assert!(approx_eq(extents.min_bottom, 50.0));
     }
 
     #[test]
@@ -1159,6 +1816,35 @@ mod tests {
         assert!(has_do, "expected Do operator for image rendering");
     }
 
+    #[test]
+    fn test_planned_table_moved_to_new_page_leaves_start_page_resources() {
+        let img = CellImage::new(tiny_jpeg_bytes())
+            .unwrap()
+            .with_max_height(120.0);
+        let table = Table::new()
+            .with_pixel_widths(vec![200.0])
+            .add_row(Row::new(vec![Cell::from_image(img)]).with_height(200.0));
+        let plan = plan_pagination(&table, &PageBox::new(595.0, 842.0), (50.0, 100.0)).unwrap();
+        assert!(plan.starts_on_new_page);
+
+        let (mut doc, page_id) = make_test_doc();
+        let result = doc.draw_table_plan(page_id, &table, &plan).unwrap();
+
+        let has_xobjects = |page: ObjectId| {
+            let page = doc.get_dictionary(page).unwrap();
+            let resources = match page.get(b"Resources").unwrap() {
+                Object::Reference(id) => doc.get_dictionary(*id).unwrap(),
+                Object::Dictionary(dict) => dict,
+                _ => panic!("page resources should be a dictionary"),
+            };
+            resources.has(b"XObject")
+        };
+        assert_eq!(result.page_ids.len(), 1);
+        assert_ne!(result.page_ids[0], page_id);
+        assert!(has_xobjects(result.page_ids[0]));
+        assert!(!has_xobjects(page_id));
+    }
+
     #[test]
     fn test_create_table_content_rejects_image_cells() {
         let img = CellImage::new(tiny_jpeg_bytes()).unwrap();
@@ -1332,6 +2018,43 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_overlay_extgstate_registered_for_second_table_on_page() {
+        let (mut doc, page_id) = make_test_doc();
+        let overlay_table = || {
+            let img = CellImage::new(tiny_jpeg_bytes())
+                .unwrap()
+                .with_max_height(120.0)
+                .with_overlay(table::ImageOverlay::new("test date"));
+            Table::new()
+                .with_pixel_widths(vec![200.0])
+                .add_row(Row::new(vec![Cell::from_image(img)]))
+        };
+
+        doc.draw_table(page_id, overlay_table(), (50.0, 750.0))
+            .unwrap();
+        doc.draw_table(page_id, overlay_table(), (50.0, 500.0))
+            .unwrap();
+
+        // The ExtGState drawn by the second table is the one registered
+        let resources_id = doc
+            .get_dictionary(page_id)
+            .unwrap()
+            .get(b"Resources")
+            .and_then(Object::as_reference)
+            .unwrap();
+        let gstate_id = doc
+            .get_dictionary(resources_id)
+            .unwrap()
+            .get(b"ExtGState")
+            .and_then(Object::as_dict)
+            .and_then(|gs_dict| gs_dict.get(b"GSTblOvl"))
+            .and_then(Object::as_reference)
+            .unwrap();
+        let gstate = doc.get_dictionary(gstate_id).unwrap();
+        assert_eq!(gstate.get(b"ca").and_then(Object::as_float).unwrap(), 0.5);
+    }
+
     #[test]
     fn test_paginated_overlay_images_register_gstate_on_all_pages() {
         const PAGE_HEIGHT: f32 = 842.0;
@@ -1378,4 +2101,145 @@ mod tests {
             "expected gs operator on continuation page for overlay rendering"
         );
     }
+
+    #[test]
+    fn test_draw_table_returns_row_and_cell_rects() {
+        let (mut doc, page_id) = make_test_doc();
+        let table = Table::new()
+            .with_pixel_widths(vec![100.0, 150.0, 50.0])
+            .add_row(
+                Row::new(vec![
+                    Cell::new("span").with_rowspan(2),
+                    Cell::new("wide").with_colspan(2),
+                ])
+                .with_height(20.0),
+            )
+            .add_row(Row::new(vec![Cell::new("b"), Cell::new("c")]).with_height(30.0));
+
+        let result = doc
+            .draw_table_with_placements(page_id, table, (50.0, 700.0))
+            .unwrap();
+
+        assert_eq!(result.page_ids, vec![page_id]);
+        assert_eq!(result.rows.len(), 2);
+        assert_eq!(
+            result.rows[1].rect,
+            Rect {
+                x: 50.0,
+                y: 650.0,
+                width: 300.0,
+                height: 30.0,
+            }
+        );
+        let cell = |row, column| {
+            result
+                .cells
+                .iter()
+                .find(|c| c.row == row && c.column == column)
+                .map(|c| c.rect)
+        };
+        assert_eq!(
+            cell(0, 0),
+            Some(Rect {
+                x: 50.0,
+                y: 650.0,
+                width: 100.0,
+                height: 50.0,
+            })
+        );
+        assert_eq!(
+            cell(0, 1),
+            Some(Rect {
+                x: 150.0,
+                y: 680.0,
+                width: 200.0,
+                height: 20.0,
+            })
+        );
+        assert_eq!(cell(1, 2).map(|r| (r.x, r.y)), Some((300.0, 650.0)));
+        assert_eq!(result.final_position, (50.0, 650.0));
+    }
+
+    #[test]
+    fn test_paginated_result_places_repeated_headers_on_each_page() {
+        let (mut doc, page_id) = make_test_doc();
+        let table = with_header_row(numbered_rows_table(40));
+
+        let result = doc
+            .draw_table_with_pagination(page_id, table, (50.0, 792.0))
+            .unwrap();
+
+        assert_eq!(result.total_pages, 2);
+        let headers: Vec<_> = result.cells.iter().filter(|c| c.row == 0).collect();
+        assert_eq!(headers.len(), 2);
+        assert_eq!(headers[1].page_id, result.page_ids[1]);
+        assert!(approx_eq(headers[1].rect.top(), 842.0 - DEFAULT_MARGIN));
+
+        let last = result.rows.last().unwrap();
+        assert_eq!(last.row, 40);
+        assert_eq!(last.page_id, result.page_ids[1]);
+        assert!(approx_eq(last.rect.y, result.final_position.1));
+    }
+
+    #[test]
+    fn test_draw_table_with_layout_reuses_borrowed_table() {
+        let (mut doc, first_page) = make_test_doc();
+        let second_page =
+            insert_page_after(&mut doc, first_page, dictionary! { "Type" => "Page" }).unwrap();
+        let table = Table::new()
+            .with_pixel_widths(vec![80.0, 80.0])
+            .add_row(Row::new(vec![Cell::new("A"), Cell::new("B")]));
+        let layout = layout::calculate_layout(&table).unwrap();
+
+        for page_id in [first_page, second_page] {
+            let result = doc
+                .draw_table_with_layout(page_id, &table, &layout, (50.0, 700.0))
+                .unwrap();
+            assert_eq!(result.page_ids, vec![page_id]);
+            assert_eq!(page_texts(&doc, page_id), vec!["A", "B"]);
+        }
+
+        let other = Table::new().add_row(Row::new(vec![Cell::new("A")]));
+        assert!(matches!(
+            doc.draw_table_with_pagination_and_layout(first_page, &other, &layout, (50.0, 700.0)),
+            Err(TableError::LayoutError(_))
+        ));
+
+        // Same grid, other text
+        let edited = Table::new()
+            .with_pixel_widths(vec![80.0, 80.0])
+            .add_row(Row::new(vec![Cell::new("A"), Cell::new("a much longer B")]));
+        assert!(matches!(
+            doc.draw_table_with_layout(first_page, &edited, &layout, (50.0, 700.0)),
+            Err(TableError::LayoutError(_))
+        ));
+    }
+
+    #[test]
+    fn test_drawing_uses_lines_wrapped_by_layout() {
+        let (mut doc, page_id) = make_test_doc();
+        let table = Table::new()
+            .with_pixel_widths(vec![80.0])
+            .add_row(Row::new(vec![
+                Cell::new("alpha beta gamma delta epsilon").with_wrap(true),
+            ]));
+        let mut layout = layout::calculate_layout(&table).unwrap();
+        let wrapped: Vec<String> = layout
+            .text_lines(0, 0)
+            .iter()
+            .map(|line| line.text.clone())
+            .collect();
+        assert!(wrapped.len() > 1);
+
+        doc.draw_table_with_layout(page_id, &table, &layout, (50.0, 700.0))
+            .unwrap();
+        assert_eq!(page_texts(&doc, page_id), wrapped);
+
+        // Drawing takes the cached lines as they are, without wrapping again
+        layout.text_lines[0][0].truncate(1);
+        let (mut doc, page_id) = make_test_doc();
+        doc.draw_table_with_layout(page_id, &table, &layout, (50.0, 700.0))
+            .unwrap();
+        assert_eq!(page_texts(&doc, page_id), wrapped[..1]);
+    }
 }
@@ -0,0 +1,973 @@
+//! Page geometry read from the PDF page tree
+
+use crate::Result;
+use crate::constants::{A4_HEIGHT, A4_WIDTH};
+use crate::error::TableError;
+use crate::font::FontMetrics;
+use crate::standard_fonts::StandardFont;
+use crate::style::{Alignment, Color, TableStyle};
+use lopdf::content::Operation;
+use lopdf::{Dictionary, Document, Object, ObjectId};
+use std::collections::HashMap;
+use tracing::trace;
+
+/// Visible area of a page in default user space: its CropBox clipped to its
+/// MediaBox, or the MediaBox alone
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PageBox {
+    pub left: f32,
+    pub bottom: f32,
+    pub right: f32,
+    pub top: f32,
+    /// Clockwise rotation applied when the page is displayed (0, 90, 180 or 270)
+    pub rotation: u16,
+}
+
+impl PageBox {
+    /// Page box with the origin at the bottom-left corner
+    pub fn new(width: f32, height: f32) -> Self {
+        Self {
+            left: 0.0,
+            bottom: 0.0,
+            right: width,
+            top: height,
+            rotation: 0,
+        }
+    }
+
+    /// Read the visible area of a page, following attributes inherited from
+    /// the page tree. Pages without a MediaBox are treated as A4.
+    pub fn of_page(doc: &Document, page_id: ObjectId) -> Result<Self> {
+        doc.get_dictionary(page_id)
+            .map_err(|_| TableError::PageNotFound(page_id))?;
+
+        let media_box = match inherited_attribute(doc, page_id, b"MediaBox") {
+            Some(media_box) => Self::from_rectangle(doc, media_box)?,
+            None => Self::new(A4_WIDTH, A4_HEIGHT),
+        };
+        let visible = match inherited_attribute(doc, page_id, b"CropBox") {
+            Some(crop_box) => {
+                let crop_box = Self::from_rectangle(doc, crop_box)?;
+                Self {
+                    left: crop_box.left.max(media_box.left),
+                    bottom: crop_box.bottom.max(media_box.bottom),
+                    right: crop_box.right.min(media_box.right),
+                    top: crop_box.top.min(media_box.top),
+                    rotation: 0,
+                }
+            }
+            None => media_box,
+        };
+
+        let rotation = inherited_attribute(doc, page_id, b"Rotate")
+            .and_then(|rotate| doc.dereference(rotate).ok()?.1.as_i64().ok())
+            .unwrap_or(0)
+            .rem_euclid(360);
+        if rotation % 90 != 0 {
+            return Err(TableError::DimensionError(format!(
+                "Page rotation {} is not a multiple of 90",
+                rotation
+            )));
+        }
+
+        Ok(Self {
+            rotation: rotation as u16,
+            ..visible
+        })
+    }
+
+    /// Parse a PDF rectangle array, whose corners may be given in any order
+    fn from_rectangle(doc: &Document, rectangle: &Object) -> Result<Self> {
+        let invalid = || TableError::DimensionError(format!("Invalid page box {:?}", rectangle));
+        let (_, rectangle) = doc.dereference(rectangle)?;
+        let values = rectangle
+            .as_array()
+            .map_err(|_| invalid())?
+            .iter()
+            .map(|value| doc.dereference(value).ok()?.1.as_float().ok())
+            .collect::<Option<Vec<f32>>>()
+            .filter(|values| values.len() == 4)
+            .ok_or_else(invalid)?;
+
+        Ok(Self {
+            left: values[0].min(values[2]),
+            bottom: values[1].min(values[3]),
+            right: values[0].max(values[2]),
+            top: values[1].max(values[3]),
+            rotation: 0,
+        })
+    }
+
+    pub fn width(&self) -> f32 {
+        self.right - self.left
+    }
+
+    pub fn height(&self) -> f32 {
+        self.top - self.bottom
+    }
+
+    /// The page as it is displayed: upright, with the origin at the bottom-left
+    /// corner of the visible area. Tables are placed and paginated in this box.
+    pub fn visible(&self) -> Self {
+        match self.rotation {
+            90 | 270 => Self::new(self.height(), self.width()),
+            _ => Self::new(self.width(), self.height()),
+        }
+    }
+
+    /// Matrix (for the `cm` operator) mapping the coordinates of [`visible`](Self::visible)
+    /// to default user space, or `None` when they coincide
+    pub fn transform(&self) -> Option<[f32; 6]> {
+        let matrix = match self.rotation {
+            90 => [0.0, 1.0, -1.0, 0.0, self.right, self.bottom],
+            180 => [-1.0, 0.0, 0.0, -1.0, self.right, self.top],
+            270 => [0.0, -1.0, 1.0, 0.0, self.left, self.top],
+            _ => [1.0, 0.0, 0.0, 1.0, self.left, self.bottom],
+        };
+        (matrix != [1.0, 0.0, 0.0, 1.0, 0.0, 0.0]).then_some(matrix)
+    }
+
+    /// X coordinate of a table's left edge, following the style's horizontal
+    /// alignment within the margins, or `x` when the style has none
+    pub(crate) fn table_x(&self, style: &TableStyle, table_width: f32, x: f32) -> f32 {
+        let region = Self {
+            left: self.left + style.left_margin,
+            right: self.right - style.right_margin,
+            ..*self
+        };
+        region.aligned_x(style.horizontal_alignment, table_width, x)
+    }
+
+    /// X coordinate of a table's left edge aligned within this box, or `x`
+    /// when there is no alignment
+    pub(crate) fn aligned_x(&self, alignment: Option<Alignment>, table_width: f32, x: f32) -> f32 {
+        match alignment {
+            None => x,
+            Some(Alignment::Left) => self.left,
+            Some(Alignment::Center) => self.left + (self.width() - table_width) / 2.0,
+            Some(Alignment::Right) => self.right - table_width,
+        }
+    }
+}
+
+/// An A4 page, on which tables are laid out when no page is given, like
+/// pages without a MediaBox
+impl Default for PageBox {
+    fn default() -> Self {
+        Self::new(A4_WIDTH, A4_HEIGHT)
+    }
+}
+
+/// Rectangle in the visible page coordinates, with `y` at its bottom edge
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rect {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl Rect {
+    pub fn top(&self) -> f32 {
+        self.y + self.height
+    }
+
+    pub fn right(&self) -> f32 {
+        self.x + self.width
+    }
+}
+
+/// A page created to continue a paginated table
+#[derive(Debug, Clone, Copy)]
+pub struct ContinuationPage {
+    pub page_id: ObjectId,
+    /// Area of the page available to the table, in the visible page
+    /// coordinates (see [`PageBox::visible`]) with any margins already applied.
+    /// Rows are drawn from its top down to its bottom, and the table is aligned
+    /// within it following `TableStyle::horizontal_alignment` (or keeps its x
+    /// position when that is not set).
+    pub region: PageBox,
+}
+
+/// Creates the pages a paginated table continues on, in place of the default
+/// copy of the previous page's boxes and resources.
+///
+/// Implementations can stamp a template onto the page, use another page size
+/// or orientation, or insert the page anywhere in the page tree. Continuation
+/// pages keep the table's column widths.
+pub trait PageFactory: Send + Sync {
+    /// Create the page following `previous_page_id`, the `page_index`-th
+    /// (zero-based) page of the table
+    fn continuation_page(
+        &self,
+        doc: &mut Document,
+        previous_page_id: ObjectId,
+        page_index: usize,
+    ) -> Result<ContinuationPage>;
+}
+
+/// A page of a paginated table, passed to [`PageDecorator`]
+#[derive(Debug, Clone, Copy)]
+pub struct DecoratedPage {
+    pub page_id: ObjectId,
+    /// Zero-based index of the page among the table's pages
+    pub page_index: usize,
+    /// Number of pages the table is drawn on
+    pub total_pages: usize,
+    /// Area covered by the table on this page
+    pub table_bounds: Rect,
+    /// The visible page (see [`PageBox::visible`]), in whose coordinates
+    /// decorations are drawn
+    pub page: PageBox,
+    /// Area of the page the table was laid out in, within its margins or as
+    /// given by the page factory
+    pub region: PageBox,
+}
+
+/// Draws on every page of a paginated table once the table is laid out on
+/// all of them, e.g. running headers, footers and "Page X of Y" numbers.
+///
+/// Operations are drawn in the visible page coordinates, on top of the table,
+/// and are marked as artifacts when drawing with a tagged-cell hook.
+pub trait PageDecorator: Send + Sync {
+    fn decorate(&self, page: &DecoratedPage) -> Vec<Operation>;
+
+    /// Standard fonts the decorations draw text with, added to the resources
+    /// of every decorated page
+    fn standard_fonts(&self) -> Vec<StandardFont> {
+        Vec::new()
+    }
+}
+
+/// Page numbers centered below the table, from a template such as
+/// `"Page {page} of {total}"`
+#[derive(Debug, Clone)]
+pub struct PageNumbers {
+    /// Text with `{page}` (one-based) and `{total}` placeholders
+    pub template: String,
+    pub font: StandardFont,
+    pub font_size: f32,
+    /// Baseline of the text in the visible page coordinates, or `None` for
+    /// the middle of the space below the table's region
+    pub y: Option<f32>,
+    pub color: Color,
+}
+
+impl PageNumbers {
+    /// Numbers in Helvetica 9pt, centered in the bottom margin
+    pub fn new(template: impl Into<String>) -> Self {
+        Self {
+            template: template.into(),
+            font: StandardFont::Helvetica,
+            font_size: 9.0,
+            y: None,
+            color: Color::black(),
+        }
+    }
+
+    /// Set the font and size
+    pub fn with_font(mut self, font: StandardFont, size: f32) -> Self {
+        self.font = font;
+        self.font_size = size;
+        self
+    }
+
+    /// Set the baseline of the text
+    pub fn with_y(mut self, y: f32) -> Self {
+        self.y = Some(y);
+        self
+    }
+
+    /// Set the text color
+    pub fn with_color(mut self, color: Color) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// The text for a page
+    pub fn text(&self, page: &DecoratedPage) -> String {
+        self.template
+            .replace("{page}", &(page.page_index + 1).to_string())
+            .replace("{total}", &page.total_pages.to_string())
+    }
+}
+
+impl PageDecorator for PageNumbers {
+    fn decorate(&self, page: &DecoratedPage) -> Vec<Operation> {
+        let text = self.text(page);
+        let text_width = self.font.text_width(&text, self.font_size);
+        let x = page.table_bounds.x + (page.table_bounds.width - text_width) / 2.0;
+        let y = self
+            .y
+            .unwrap_or((page.page.bottom + page.region.bottom) / 2.0);
+        vec![
+            Operation::new("BT", vec![]),
+            Operation::new(
+                "rg",
+                vec![
+                    self.color.r.into(),
+                    self.color.g.into(),
+                    self.color.b.into(),
+                ],
+            ),
+            Operation::new(
+                "Tf",
+                vec![
+                    Object::Name(self.font.resource_name().as_bytes().to_vec()),
+                    self.font_size.into(),
+                ],
+            ),
+            Operation::new("Td", vec![x.into(), y.into()]),
+            Operation::new(
+                "Tj",
+                vec![Object::String(
+                    self.font.encode_text(&text),
+                    lopdf::StringFormat::Literal,
+                )],
+            ),
+            Operation::new("ET", vec![]),
+        ]
+    }
+
+    fn standard_fonts(&self) -> Vec<StandardFont> {
+        vec![self.font]
+    }
+}
+
+/// Insert a page into the page tree directly after `previous_page_id`, under
+/// the same parent, and update the page count of every ancestor.
+///
+/// The `Parent` entry of `page` is set; everything else is left to the caller.
+pub fn insert_page_after(
+    doc: &mut Document,
+    previous_page_id: ObjectId,
+    mut page: Dictionary,
+) -> Result<ObjectId> {
+    let parent_id = doc
+        .get_dictionary(previous_page_id)
+        .map_err(|_| TableError::PageNotFound(previous_page_id))?
+        .get(b"Parent")
+        .and_then(Object::as_reference)
+        .map_err(|_| TableError::DrawingError("Could not find parent Pages object".to_string()))?;
+
+    page.set("Parent", parent_id);
+    let page_id = doc.add_object(page);
+
+    let kids = doc
+        .get_dictionary_mut(parent_id)?
+        .get_mut(b"Kids")
+        .and_then(Object::as_array_mut)?;
+    let slot = kids
+        .iter()
+        .position(|kid| kid.as_reference().is_ok_and(|id| id == previous_page_id))
+        .map_or(kids.len(), |idx| idx + 1);
+    kids.insert(slot, page_id.into());
+
+    // Every Pages node up to the root counts the new page
+    let mut node_id = parent_id;
+    for _ in 0..64 {
+        let node = doc.get_dictionary_mut(node_id)?;
+        if let Ok(count) = node.get(b"Count").and_then(Object::as_i64) {
+            node.set("Count", count + 1);
+        }
+        match node.get(b"Parent").and_then(Object::as_reference) {
+            Ok(parent) => node_id = parent,
+            Err(_) => break,
+        }
+    }
+
+    trace!("Inserted page {:?} after {:?}", page_id, previous_page_id);
+    Ok(page_id)
+}
+
+/// Look up a page attribute on the page or, for inheritable attributes such as
+/// MediaBox, on the nearest ancestor in the page tree that defines it
+pub(crate) fn inherited_attribute<'a>(
+    doc: &'a Document,
+    page_id: ObjectId,
+    key: &[u8],
+) -> Option<&'a Object> {
+    let mut node_id = page_id;
+    // Bounded walk guards against cycles in malformed page trees
+    for _ in 0..64 {
+        let node = doc.get_dictionary(node_id).ok()?;
+        if let Ok(value) = node.get(key) {
+            return Some(value);
+        }
+        node_id = node.get(b"Parent").and_then(Object::as_reference).ok()?;
+    }
+    None
+}
+
+/// Number of objects referencing each resources dictionary of a document,
+/// counted once for all the pages a table adds resources to
+pub(crate) struct ResourceReferences(HashMap<ObjectId, usize>);
+
+impl ResourceReferences {
+    pub(crate) fn new(doc: &Document) -> Self {
+        let mut counts = HashMap::new();
+        for object in doc.objects.values() {
+            if let Ok(id) = object
+                .as_dict()
+                .and_then(|dict| dict.get(b"Resources"))
+                .and_then(Object::as_reference)
+            {
+                *counts.entry(id).or_insert(0) += 1;
+            }
+        }
+        Self(counts)
+    }
+}
+
+/// Add a font to the resources of a page.
+pub(crate) fn add_font_resource(
+    doc: &mut Document,
+    references: &mut ResourceReferences,
+    page_id: ObjectId,
+    name: &str,
+    font_id: ObjectId,
+) -> Result<()> {
+    add_page_resource(doc, references, page_id, "Font", name, font_id)
+}
+
+/// Add a named resource, such as a font or an ExtGState, to the resources of
+/// a page.
+///
+/// The page gets its own copy of its resources and of the `category`
+/// dictionary first, whether they are inherited, inline or shared with other
+/// pages, so the resources it already uses stay available and pages sharing
+/// those dictionaries are left untouched. `references` must have been
+/// counted after the page was added to the document.
+pub(crate) fn add_page_resource(
+    doc: &mut Document,
+    references: &mut ResourceReferences,
+    page_id: ObjectId,
+    category: &str,
+    name: &str,
+    object_id: ObjectId,
+) -> Result<()> {
+    let resources_id = own_resources_id(doc, references, page_id)?;
+    let resources = doc.get_dictionary(resources_id)?;
+    let mut entries =
+        resolve_dictionary(doc, resources.get(category.as_bytes()).ok()).unwrap_or_default();
+
+    entries.set(name, object_id);
+    doc.get_dictionary_mut(resources_id)?.set(category, entries);
+    Ok(())
+}
+
+/// Id of a resources dictionary referenced by the page and by no other
+/// object, copying the page's current resources into one when needed
+fn own_resources_id(
+    doc: &mut Document,
+    references: &mut ResourceReferences,
+    page_id: ObjectId,
+) -> Result<ObjectId> {
+    let current = doc
+        .get_dictionary(page_id)?
+        .get(b"Resources")
+        .and_then(Object::as_reference)
+        .ok();
+    if let Some(id) = current {
+        let shared = references.0.get(&id).is_some_and(|&count| count > 1);
+        if !shared && doc.get_dictionary(id).is_ok() {
+            return Ok(id);
+        }
+    }
+
+    let resources = resolve_dictionary(doc, inherited_attribute(doc, page_id, b"Resources"))
+        .unwrap_or_default();
+    let resources_id = doc.add_object(resources);
+    doc.get_dictionary_mut(page_id)?
+        .set("Resources", resources_id);
+    if let Some(count) = current.and_then(|id| references.0.get_mut(&id)) {
+        *count -= 1;
+    }
+    references.0.insert(resources_id, 1);
+    Ok(resources_id)
+}
+
+/// Copy of a dictionary given inline or by reference
+fn resolve_dictionary(doc: &Document, object: Option<&Object>) -> Option<Dictionary> {
+    match object {
+        Some(Object::Reference(id)) => doc.get_dictionary(*id).ok().cloned(),
+        Some(Object::Dictionary(dict)) => Some(dict.clone()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TableDrawing;
+    use crate::constants::DEFAULT_MARGIN;
+    use crate::layout;
+    use crate::style::TableStyle;
+    use crate::table::{Cell, Row, Table};
+    use crate::test_support::*;
+    use lopdf::dictionary;
+
+    #[test]
+    fn test_page_box_inherits_media_box_and_clips_crop_box() {
+        let mut doc = Document::with_version("1.7");
+        let pages_id = doc.add_object(dictionary! {
+            "Type" => "Pages",
+            "Kids" => vec![],
+            "Count" => 1,
+            "MediaBox" => vec![0.into(), 0.into(), 612.into(), 792.into()],
+        });
+        let page_id = doc.add_object(dictionary! {
+            "Type" => "Page",
+            "Parent" => pages_id,
+            "CropBox" => vec![36.into(), 36.into(), 700.into(), 756.into()],
+        });
+
+        let page = PageBox::of_page(&doc, page_id).unwrap();
+        assert_eq!(
+            page,
+            PageBox {
+                left: 36.0,
+                bottom: 36.0,
+                right: 612.0,
+                top: 756.0,
+                rotation: 0,
+            }
+        );
+        assert_eq!(page.width(), 576.0);
+    }
+
+    #[test]
+    fn test_rotated_page_transform_maps_visible_corners() {
+        let page = PageBox {
+            left: 10.0,
+            bottom: 20.0,
+            right: 610.0,
+            top: 820.0,
+            rotation: 90,
+        };
+        let visible = page.visible();
+        assert_eq!((visible.width(), visible.height()), (800.0, 600.0));
+
+        // The visible top-left corner is the user-space bottom-left corner
+        let [a, b, c, d, e, f] = page.transform().unwrap();
+        let (u, v) = (0.0, visible.top);
+        assert_eq!((a * u + c * v + e, b * u + d * v + f), (10.0, 20.0));
+
+        assert_eq!(PageBox::new(595.0, 842.0).transform(), None);
+    }
+
+    #[test]
+    fn test_insert_page_after_updates_nested_counts() {
+        let mut doc = Document::with_version("1.7");
+        let root_id = doc.new_object_id();
+        let branch_id = doc.add_object(dictionary! {
+            "Type" => "Pages",
+            "Parent" => root_id,
+            "Count" => 2,
+        });
+        let first = doc.add_object(dictionary! { "Type" => "Page", "Parent" => branch_id });
+        let second = doc.add_object(dictionary! { "Type" => "Page", "Parent" => branch_id });
+        doc.get_dictionary_mut(branch_id)
+            .unwrap()
+            .set("Kids", vec![first.into(), second.into()]);
+        doc.objects.insert(
+            root_id,
+            Object::Dictionary(dictionary! {
+                "Type" => "Pages",
+                "Kids" => vec![branch_id.into()],
+                "Count" => 2,
+            }),
+        );
+
+        let inserted =
+            insert_page_after(&mut doc, first, dictionary! { "Type" => "Page" }).unwrap();
+
+        let branch = doc.get_dictionary(branch_id).unwrap();
+        assert_eq!(
+            branch.get(b"Kids").unwrap().as_array().unwrap(),
+            &vec![first.into(), inserted.into(), second.into()]
+        );
+        assert_eq!(branch.get(b"Count").unwrap().as_i64().unwrap(), 3);
+        let root = doc.get_dictionary(root_id).unwrap();
+        assert_eq!(root.get(b"Count").unwrap().as_i64().unwrap(), 3);
+    }
+
+    #[test]
+    fn test_table_x_aligns_within_margins() {
+        let page = PageBox::new(600.0, 800.0);
+        let mut style = TableStyle {
+            left_margin: 40.0,
+            right_margin: 60.0,
+            ..Default::default()
+        };
+        assert_eq!(page.table_x(&style, 300.0, 75.0), 75.0);
+
+        style.horizontal_alignment = Some(Alignment::Left);
+        assert_eq!(page.table_x(&style, 300.0, 75.0), 40.0);
+        style.horizontal_alignment = Some(Alignment::Center);
+        assert_eq!(page.table_x(&style, 300.0, 75.0), 140.0);
+        style.horizontal_alignment = Some(Alignment::Right);
+        assert_eq!(page.table_x(&style, 300.0, 75.0), 240.0);
+    }
+
+    #[test]
+    fn test_add_font_resource_copies_inherited_resources() {
+        let mut doc = Document::with_version("1.7");
+        let helvetica = doc.add_object(dictionary! { "Type" => "Font" });
+        let embedded = doc.add_object(dictionary! { "Type" => "Font" });
+        let resources_id = doc.add_object(dictionary! {
+            "Font" => dictionary! { "F1" => helvetica },
+        });
+        let pages_id = doc.add_object(dictionary! {
+            "Type" => "Pages",
+            "Kids" => vec![],
+            "Count" => 0,
+            "Resources" => resources_id,
+        });
+        let page_id = doc.add_object(dictionary! { "Type" => "Page", "Parent" => pages_id });
+
+        let mut references = ResourceReferences::new(&doc);
+        add_font_resource(&mut doc, &mut references, page_id, "EF0", embedded).unwrap();
+
+        let fonts = doc.get_page_fonts(page_id).unwrap();
+        assert_eq!(fonts.len(), 2);
+        assert!(fonts.contains_key(b"F1".as_slice()));
+        assert!(fonts.contains_key(b"EF0".as_slice()));
+        // The shared resources of the parent are left untouched
+        let shared = doc.get_dictionary(resources_id).unwrap();
+        assert_eq!(shared.get(b"Font").unwrap().as_dict().unwrap().len(), 1);
+
+        // Pages referencing shared resources, or a shared font dictionary,
+        // get their own copy too
+        let fonts_id = doc.add_object(dictionary! { "F1" => helvetica });
+        let shared_fonts_id = doc.add_object(dictionary! { "Font" => fonts_id });
+        for shared_id in [resources_id, shared_fonts_id] {
+            let page_id = doc.add_object(dictionary! {
+                "Type" => "Page",
+                "Parent" => pages_id,
+                "Resources" => shared_id,
+            });
+            let other_id = doc.add_object(dictionary! {
+                "Type" => "Page",
+                "Parent" => pages_id,
+                "Resources" => shared_id,
+            });
+
+            let mut references = ResourceReferences::new(&doc);
+            add_font_resource(&mut doc, &mut references, page_id, "EF0", embedded).unwrap();
+
+            let fonts = doc.get_page_fonts(page_id).unwrap();
+            assert!(fonts.contains_key(b"F1".as_slice()));
+            assert!(fonts.contains_key(b"EF0".as_slice()));
+            let other_fonts = doc.get_page_fonts(other_id).unwrap();
+            assert_eq!(other_fonts.len(), 1, "shared resources were modified");
+        }
+        assert_eq!(doc.get_dictionary(fonts_id).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_resource_references_follow_copied_resources() {
+        let mut doc = Document::with_version("1.7");
+        let font = doc.add_object(dictionary! { "Type" => "Font" });
+        let resources_id = doc.add_object(dictionary! {});
+        let first = doc.add_object(dictionary! { "Type" => "Page", "Resources" => resources_id });
+        let second = doc.add_object(dictionary! { "Type" => "Page", "Resources" => resources_id });
+
+        let mut references = ResourceReferences::new(&doc);
+        add_font_resource(&mut doc, &mut references, first, "F1", font).unwrap();
+        add_font_resource(&mut doc, &mut references, second, "F1", font).unwrap();
+        add_font_resource(&mut doc, &mut references, first, "F2", font).unwrap();
+
+        // The first page gets a copy once; the second keeps the original
+        let resources_of = |page: ObjectId| {
+            doc.get_dictionary(page)
+                .unwrap()
+                .get(b"Resources")
+                .unwrap()
+                .as_reference()
+                .unwrap()
+        };
+        assert_ne!(resources_of(first), resources_id);
+        assert_eq!(resources_of(second), resources_id);
+        assert_eq!(doc.get_page_fonts(first).unwrap().len(), 2);
+        assert_eq!(doc.get_page_fonts(second).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_table_width_follows_inherited_media_box_and_margins() {
+        // A Letter MediaBox inherited from the page tree root
+        let (mut doc, page_id) = make_test_doc();
+        let page = doc.get_dictionary_mut(page_id).unwrap();
+        page.remove(b"MediaBox");
+        let parent_id = page.get(b"Parent").and_then(Object::as_reference).unwrap();
+        doc.get_dictionary_mut(parent_id)
+            .unwrap()
+            .set("MediaBox", vec![0.into(), 0.into(), 612.into(), 792.into()]);
+        let style = TableStyle {
+            left_margin: 36.0,
+            right_margin: 76.0,
+            ..Default::default()
+        };
+        let table = Table::new().with_style(style).add_row(Row::new(vec![
+            Cell::new("A"),
+            Cell::new("word ".repeat(100)).with_wrap(true),
+        ]));
+
+        doc.draw_table(page_id, table, (36.0, 700.0)).unwrap();
+
+        let extents = page_rect_extents(&doc, page_id).expect("table should draw rectangles");
+        assert!(approx_eq(extents.max_right, 612.0 - 76.0));
+    }
+
+    #[test]
+    fn test_table_width_respects_crop_box() {
+        let (mut doc, page_id) = make_test_doc();
+        if let Ok(Object::Dictionary(page)) = doc.get_object_mut(page_id) {
+            page.set(
+                "CropBox",
+                vec![100.into(), 0.into(), 495.into(), 842.into()],
+            );
+        }
+        let table = Table::new().add_row(Row::new(vec![
+            Cell::new("word ".repeat(100)).with_wrap(true),
+        ]));
+
+        let layout =
+            layout::calculate_layout_for_page(&table, &PageBox::of_page(&doc, page_id).unwrap())
+                .unwrap();
+        assert!(approx_eq(layout.total_width, 395.0 - 2.0 * DEFAULT_MARGIN));
+    }
+
+    #[test]
+    fn test_horizontal_alignment_centers_and_right_aligns_table() {
+        for (alignment, expected_right) in [
+            (Alignment::Center, (595.0 + 200.0) / 2.0),
+            (Alignment::Right, 595.0 - DEFAULT_MARGIN),
+        ] {
+            let (mut doc, page_id) = make_test_doc();
+            let style = TableStyle {
+                horizontal_alignment: Some(alignment),
+                ..Default::default()
+            };
+            let table = Table::new()
+                .with_style(style)
+                .with_pixel_widths(vec![200.0])
+                .add_row(Row::new(vec![Cell::new("A")]));
+
+            doc.draw_table_with_pagination(page_id, table, (0.0, 700.0))
+                .unwrap();
+
+            let extents = page_rect_extents(&doc, page_id).expect("table should draw rectangles");
+            assert!(
+                approx_eq(extents.max_right, expected_right),
+                "{alignment:?}: right edge {} != {expected_right}",
+                extents.max_right
+            );
+        }
+    }
+
+    #[test]
+    fn test_rotated_page_paginates_in_visible_coordinates() {
+        let (mut doc, page_id) = make_test_doc();
+        if let Ok(Object::Dictionary(page)) = doc.get_object_mut(page_id) {
+            page.set("Rotate", 90);
+        }
+        let mut table = numbered_rows_table(40);
+        table.style.page_height = None;
+
+        // Landscape: 595 - 2 * 50 points of rows per page
+        let result = doc
+            .draw_table_with_pagination(page_id, table, (50.0, 545.0))
+            .unwrap();
+        assert_eq!(result.total_pages, 3);
+
+        for &page in &result.page_ids {
+            let ops = page_content_operations(&doc, page);
+            assert_eq!(ops[0].operator, "q");
+            assert_eq!(ops[1].operator, "cm");
+            let matrix: Vec<f32> = ops[1].operands.iter().filter_map(object_to_f32).collect();
+            assert_eq!(matrix, vec![0.0, 1.0, -1.0, 0.0, 595.0, 0.0]);
+            assert_eq!(ops.last().unwrap().operator, "Q");
+            assert_eq!(
+                PageBox::of_page(&doc, page).unwrap().rotation,
+                90,
+                "continuation pages keep the rotation"
+            );
+        }
+    }
+
+    #[test]
+    fn test_offset_crop_box_translates_table() {
+        let (mut doc, page_id) = make_test_doc();
+        if let Ok(Object::Dictionary(page)) = doc.get_object_mut(page_id) {
+            page.set(
+                "CropBox",
+                vec![100.into(), 100.into(), 495.into(), 742.into()],
+            );
+        }
+        let table = Table::new()
+            .with_pixel_widths(vec![100.0])
+            .add_row(Row::new(vec![Cell::new("A")]));
+
+        doc.add_table_to_page(page_id, table).unwrap();
+
+        let ops = page_content_operations(&doc, page_id);
+        let matrix: Vec<f32> = ops[1].operands.iter().filter_map(object_to_f32).collect();
+        assert_eq!(matrix, vec![1.0, 0.0, 0.0, 1.0, 100.0, 100.0]);
+        let extents = page_rect_extents(&doc, page_id).unwrap();
+        assert!(approx_eq(extents.max_top, 642.0 - DEFAULT_MARGIN - 50.0));
+    }
+
+    #[test]
+    fn test_page_factory_creates_continuation_pages() {
+        let (mut doc, page_id) = make_test_doc();
+        let factory = std::sync::Arc::new(LetterheadPages {
+            indexes: std::sync::Mutex::new(Vec::new()),
+        });
+        let mut table = numbered_rows_table(40);
+        table.page_factory = Some(factory.clone());
+        table.style.horizontal_alignment = Some(Alignment::Right);
+
+        let result = doc
+            .draw_table_with_pagination(page_id, table, (50.0, 792.0))
+            .unwrap();
+
+        // 24 rows on the start page, then up to 17 in the 520pt region
+        assert_eq!(result.total_pages, 2);
+        assert_eq!(*factory.indexes.lock().unwrap(), vec![1]);
+        let texts = page_texts(&doc, result.page_ids[1]);
+        assert_eq!(texts.first().map(String::as_str), Some("item-24"));
+
+        // Rows are drawn in the region, below the letterhead and right-aligned
+        let rects: Vec<Vec<f32>> = page_content_operations(&doc, result.page_ids[1])
+            .iter()
+            .filter(|op| op.operator == "re")
+            .map(|op| op.operands.iter().filter_map(object_to_f32).collect())
+            .collect();
+        assert_eq!(rects[0], vec![0.0, 650.0, 500.0, 50.0]);
+        let rows_top = rects[1..]
+            .iter()
+            .map(|r| r[1] + r[3])
+            .fold(f32::MIN, f32::max);
+        let rows_right = rects[1..]
+            .iter()
+            .map(|r| r[0] + r[2])
+            .fold(f32::MIN, f32::max);
+        assert!(approx_eq(rows_top, 600.0));
+        assert!(approx_eq(rows_right, 470.0));
+        assert!(approx_eq(result.final_position.1, 600.0 - 16.0 * 30.0));
+    }
+
+    #[test]
+    fn test_continuation_pages_follow_current_page_in_nested_tree() {
+        let (mut doc, first_page) = make_test_doc();
+        let root_id = doc
+            .catalog()
+            .unwrap()
+            .get(b"Pages")
+            .unwrap()
+            .as_reference()
+            .unwrap();
+        let last_page =
+            insert_page_after(&mut doc, first_page, dictionary! { "Type" => "Page" }).unwrap();
+        // Move both pages under an intermediate Pages node
+        let branch_id = doc.add_object(dictionary! {
+            "Type" => "Pages",
+            "Parent" => root_id,
+            "Kids" => vec![first_page.into(), last_page.into()],
+            "Count" => 2,
+        });
+        doc.get_dictionary_mut(root_id)
+            .unwrap()
+            .set("Kids", vec![branch_id.into()]);
+        for page in [first_page, last_page] {
+            doc.get_dictionary_mut(page)
+                .unwrap()
+                .set("Parent", branch_id);
+        }
+
+        let result = doc
+            .draw_table_with_pagination(first_page, numbered_rows_table(50), (50.0, 792.0))
+            .unwrap();
+
+        assert_eq!(result.total_pages, 3);
+        let mut expected = result.page_ids.clone();
+        expected.push(last_page);
+        assert_eq!(page_tree_order(&doc), expected);
+        let root = doc.get_dictionary(root_id).unwrap();
+        assert_eq!(root.get(b"Count").unwrap().as_i64().unwrap(), 4);
+    }
+
+    #[test]
+    fn test_page_numbers_decorate_every_page_with_total() {
+        let (mut doc, page_id) = make_test_doc();
+        let table =
+            numbered_rows_table(50).with_page_decorator(PageNumbers::new("Page {page} of {total}"));
+
+        let result = doc
+            .draw_table_with_pagination(page_id, table, (50.0, 792.0))
+            .unwrap();
+
+        assert_eq!(result.total_pages, 3);
+        for (index, &page) in result.page_ids.iter().enumerate() {
+            let texts = page_texts(&doc, page);
+            assert_eq!(
+                texts.last().map(String::as_str),
+                Some(format!("Page {} of 3", index + 1).as_str())
+            );
+        }
+
+        // 24 rows on the first two pages, 2 on the last
+        let bounds = &result.table_bounds;
+        assert_eq!(bounds.len(), 3);
+        assert!(approx_eq(bounds[0].top(), 792.0));
+        assert!(approx_eq(bounds[0].height, 24.0 * 30.0));
+        assert!(approx_eq(bounds[2].top(), 842.0 - DEFAULT_MARGIN));
+        assert!(approx_eq(bounds[2].y, result.final_position.1));
+        assert!(approx_eq(bounds[2].width, 200.0));
+    }
+
+    #[test]
+    fn test_page_numbers_use_their_font_and_the_bottom_margin() {
+        let (mut doc, page_id) = make_test_doc();
+        let numbers =
+            PageNumbers::new("Seite {page} – {total}").with_font(StandardFont::Courier, 10.0);
+        let table = numbered_rows_table(5).with_page_decorator(numbers.clone());
+
+        let result = doc
+            .draw_table_with_pagination(page_id, table, (50.0, 792.0))
+            .unwrap();
+
+        let operations = page_content_operations(&doc, page_id);
+        let tf = operations
+            .iter()
+            .rev()
+            .find(|op| op.operator == "Tf")
+            .unwrap();
+        assert_eq!(tf.operands[0].as_name().unwrap(), b"F2");
+        let tj = operations
+            .iter()
+            .rev()
+            .find(|op| op.operator == "Tj")
+            .unwrap();
+        assert_eq!(tj.operands[0].as_str().unwrap(), b"Seite 1 \x96 1");
+
+        // Centered under the table using Courier widths, halfway down the margin
+        let td = operations
+            .iter()
+            .rev()
+            .find(|op| op.operator == "Td")
+            .unwrap();
+        let text_width = StandardFont::Courier.text_width("Seite 1 – 1", 10.0);
+        let bounds = result.table_bounds[0];
+        let x = object_to_f32(&td.operands[0]).unwrap();
+        let y = object_to_f32(&td.operands[1]).unwrap();
+        assert!(approx_eq(x, bounds.x + (bounds.width - text_width) / 2.0));
+        assert!(approx_eq(y, DEFAULT_MARGIN / 2.0));
+
+        let page = doc.get_dictionary(page_id).unwrap();
+        let resources = resolve_dictionary(&doc, page.get(b"Resources").ok()).unwrap();
+        let fonts = resolve_dictionary(&doc, resources.get(b"Font").ok()).unwrap();
+        assert!(fonts.has(b"F2"));
+    }
+}
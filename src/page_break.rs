@@ -0,0 +1,342 @@
+//! Rows generated at page breaks when paginating (running subtotals)
+
+use crate::grid::CellGrid;
+use crate::style::CellStyle;
+use crate::table::{Cell, Row, Table};
+
+/// State of a paginated table at a page break, passed to [`PageBreakRows`]
+pub struct PageBreak<'a> {
+    /// The table being drawn
+    pub table: &'a Table,
+    /// Zero-based index of the page that ends at this break
+    pub page_index: usize,
+    /// Body rows placed so far, in order, up to and including the page ending here.
+    /// Header and footer rows are never included; a row split across pages
+    /// counts toward the page it starts on.
+    pub rows_placed: &'a [usize],
+    /// Number of trailing entries of `rows_placed` that sit on the page ending here
+    pub rows_on_page: usize,
+    pub(crate) grid: &'a CellGrid,
+}
+
+impl<'a> PageBreak<'a> {
+    /// Body rows placed on the page ending at this break
+    pub fn page_rows(&self) -> &'a [usize] {
+        &self.rows_placed[self.rows_placed.len() - self.rows_on_page..]
+    }
+
+    /// Cell declared in `row` that starts at grid column `column`.
+    ///
+    /// Returns `None` when the column is covered by a cell starting further
+    /// left (colspan) or spanning down from an earlier row (rowspan).
+    pub fn cell(&self, row: usize, column: usize) -> Option<&'a Cell> {
+        self.grid
+            .placements_in_row(row)
+            .iter()
+            .find(|p| p.col == column)
+            .map(|p| &self.table.rows[row].cells[p.cell])
+    }
+}
+
+/// Callback producing rows inserted by the paginator at every page break.
+///
+/// The returned rows must cover the table's columns exactly once (colspan is
+/// allowed, rowspan is not) and are drawn with the table's column widths.
+/// Both are called once at every page break. When the carried forward row is
+/// taller than the room kept for it, the page is planned again with fewer
+/// rows and `carried_forward` is called again for its new break.
+pub trait PageBreakRows: Send + Sync {
+    /// Row drawn at the bottom of a page the table continues from, above any footer rows
+    fn carried_forward(&self, page_break: &PageBreak<'_>) -> Option<Row>;
+
+    /// Row drawn at the top of the next page, below any repeated header rows
+    fn brought_forward(&self, page_break: &PageBreak<'_>) -> Option<Row>;
+}
+
+/// Running totals of numeric columns, carried and brought forward across pages.
+///
+/// Cell text is parsed as a number after removing thousands separators and
+/// whitespace; cells that do not parse are skipped.
+#[derive(Debug, Clone)]
+pub struct RunningSubtotals {
+    /// Grid columns to total
+    pub columns: Vec<usize>,
+    /// Grid column holding the label (must not be one of `columns`)
+    pub label_column: usize,
+    pub carried_label: String,
+    pub brought_label: String,
+    /// Digits after the decimal point in formatted totals
+    pub decimals: usize,
+    /// Style applied to every generated cell
+    pub cell_style: Option<CellStyle>,
+}
+
+impl RunningSubtotals {
+    /// Total the given grid columns, labelled in the first column
+    pub fn new(columns: Vec<usize>) -> Self {
+        Self {
+            columns,
+            label_column: 0,
+            carried_label: "Carried forward".to_string(),
+            brought_label: "Brought forward".to_string(),
+            decimals: 2,
+            cell_style: None,
+        }
+    }
+
+    /// Set the labels of the carried and brought forward rows
+    pub fn with_labels(mut self, carried: impl Into<String>, brought: impl Into<String>) -> Self {
+        self.carried_label = carried.into();
+        self.brought_label = brought.into();
+        self
+    }
+
+    /// Set the grid column holding the label
+    pub fn with_label_column(mut self, column: usize) -> Self {
+        self.label_column = column;
+        self
+    }
+
+    /// Set the number of decimals in formatted totals
+    pub fn with_decimals(mut self, decimals: usize) -> Self {
+        self.decimals = decimals;
+        self
+    }
+
+    /// Set the style applied to every generated cell
+    pub fn with_cell_style(mut self, style: CellStyle) -> Self {
+        self.cell_style = Some(style);
+        self
+    }
+
+    /// Sum of a column over the body rows placed so far
+    pub fn total(&self, page_break: &PageBreak<'_>, column: usize) -> f64 {
+        page_break
+            .rows_placed
+            .iter()
+            .filter_map(|&row| page_break.cell(row, column))
+            .filter_map(|cell| parse_amount(&cell.content))
+            .sum()
+    }
+
+    fn totals_row(&self, page_break: &PageBreak<'_>, label: &str) -> Row {
+        let cells = (0..page_break.grid.columns())
+            .map(|column| {
+                let content = if column == self.label_column {
+                    label.to_string()
+                } else if self.columns.contains(&column) {
+                    format!("{:.*}", self.decimals, self.total(page_break, column))
+                } else {
+                    String::new()
+                };
+                let cell = Cell::new(content);
+                match &self.cell_style {
+                    Some(style) => cell.with_style(style.clone()),
+                    None => cell,
+                }
+            })
+            .collect();
+        Row::new(cells)
+    }
+}
+
+impl PageBreakRows for RunningSubtotals {
+    fn carried_forward(&self, page_break: &PageBreak<'_>) -> Option<Row> {
+        Some(self.totals_row(page_break, &self.carried_label))
+    }
+
+    fn brought_forward(&self, page_break: &PageBreak<'_>) -> Option<Row> {
+        Some(self.totals_row(page_break, &self.brought_label))
+    }
+}
+
+fn parse_amount(text: &str) -> Option<f64> {
+    let cleaned: String = text
+        .chars()
+        .filter(|c| *c != ',' && !c.is_whitespace())
+        .collect();
+    cleaned.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TableDrawing;
+    use crate::style::TableStyle;
+    use crate::test_support::*;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn test_running_subtotals_sum_placed_rows() {
+        let table = Table::new()
+            .add_row(Row::new(vec![Cell::new("Item"), Cell::new("Amount")]))
+            .add_row(Row::new(vec![Cell::new("a"), Cell::new("1,000.50")]))
+            .add_row(Row::new(vec![Cell::new("b"), Cell::new("n/a")]))
+            .add_row(Row::new(vec![Cell::new("c"), Cell::new("2")]));
+        let grid = table.validated_grid().unwrap();
+        let placed = [1, 2, 3];
+        let page_break = PageBreak {
+            table: &table,
+            page_index: 0,
+            rows_placed: &placed,
+            rows_on_page: 2,
+            grid: &grid,
+        };
+
+        let subtotals = RunningSubtotals::new(vec![1]);
+        assert_eq!(page_break.page_rows(), &[2, 3]);
+        assert_eq!(subtotals.total(&page_break, 1), 1002.5);
+
+        let row = subtotals.carried_forward(&page_break).unwrap();
+        assert_eq!(row.cells[0].content, "Carried forward");
+        assert_eq!(row.cells[1].content, "1002.50");
+    }
+
+    #[test]
+    fn test_paginated_running_subtotals_carry_and_bring_forward() {
+        let (mut doc, page_id) = make_test_doc();
+
+        let style = TableStyle {
+            page_height: Some(842.0),
+            ..Default::default()
+        };
+        let mut table = Table::new()
+            .with_style(style)
+            .with_header_rows(1)
+            .with_pixel_widths(vec![150.0, 100.0])
+            .with_page_break_rows(RunningSubtotals::new(vec![1]))
+            .add_row(Row::new(vec![Cell::new("Item"), Cell::new("Amount")]).with_height(30.0));
+        for row in 0..60 {
+            table = table.add_row(
+                Row::new(vec![Cell::new(format!("item-{row}")), Cell::new("10.00")])
+                    .with_height(30.0),
+            );
+        }
+
+        let result = doc
+            .draw_table_with_pagination(page_id, table, (50.0, 792.0))
+            .expect("paginated table draw should succeed");
+        assert!(result.total_pages >= 2);
+
+        let mut running = 0.0;
+        let last = result.page_ids.len() - 1;
+        for (idx, &page) in result.page_ids.iter().enumerate() {
+            let texts = page_texts(&doc, page);
+            let items = texts.iter().filter(|t| t.starts_with("item-")).count();
+
+            if idx > 0 {
+                assert_eq!(texts[2], "Brought forward", "page {idx}: {texts:?}");
+                assert_eq!(texts[3], format!("{running:.2}"));
+            }
+            running += items as f64 * 10.0;
+
+            let carried = texts.iter().position(|t| t == "Carried forward");
+            if idx == last {
+                assert!(carried.is_none());
+            } else {
+                let carried = carried.expect("carried forward row missing");
+                assert_eq!(texts[carried + 1], format!("{running:.2}"));
+            }
+
+            let extents = page_rect_extents(&doc, page).expect("page should have rects");
+            assert!(extents.min_bottom >= 50.0 - 0.01);
+        }
+        assert_eq!(running, 600.0);
+    }
+
+    /// Counts the calls made to the running subtotals it wraps
+    struct CountedSubtotals {
+        inner: RunningSubtotals,
+        carried: AtomicUsize,
+        brought: AtomicUsize,
+    }
+
+    impl PageBreakRows for CountedSubtotals {
+        fn carried_forward(&self, page_break: &PageBreak<'_>) -> Option<Row> {
+            self.carried.fetch_add(1, Ordering::Relaxed);
+            self.inner.carried_forward(page_break)
+        }
+
+        fn brought_forward(&self, page_break: &PageBreak<'_>) -> Option<Row> {
+            self.brought.fetch_add(1, Ordering::Relaxed);
+            self.inner.brought_forward(page_break)
+        }
+    }
+
+    #[test]
+    fn test_page_break_rows_generated_once_per_break() {
+        let (mut doc, page_id) = make_test_doc();
+        let counted = Arc::new(CountedSubtotals {
+            inner: RunningSubtotals::new(vec![1]),
+            carried: AtomicUsize::new(0),
+            brought: AtomicUsize::new(0),
+        });
+        let mut table = numbered_rows_table(60).with_pixel_widths(vec![150.0, 100.0]);
+        for row in &mut table.rows {
+            row.cells.push(Cell::new("10.00"));
+        }
+        table.page_break_rows = Some(counted.clone());
+
+        let result = doc
+            .draw_table_with_pagination(page_id, table, (50.0, 792.0))
+            .expect("paginated table draw should succeed");
+
+        assert!(result.total_pages >= 3);
+        let breaks = result.total_pages - 1;
+        assert_eq!(counted.carried.load(Ordering::Relaxed), breaks);
+        assert_eq!(counted.brought.load(Ordering::Relaxed), breaks);
+    }
+
+    /// Carries forward a label naming every row placed on the page, which
+    /// wraps onto more lines as the page fills
+    struct GrowingCarriedRow;
+
+    impl PageBreakRows for GrowingCarriedRow {
+        fn carried_forward(&self, page_break: &PageBreak<'_>) -> Option<Row> {
+            let label = page_break
+                .page_rows()
+                .iter()
+                .map(|row| format!("row-{row}"))
+                .collect::<Vec<_>>()
+                .join(" ");
+            Some(Row::new(vec![
+                Cell::new(label).with_wrap(true),
+                Cell::new(""),
+            ]))
+        }
+
+        fn brought_forward(&self, _page_break: &PageBreak<'_>) -> Option<Row> {
+            None
+        }
+    }
+
+    #[test]
+    fn test_carried_forward_row_taller_than_reserved_stays_above_margin() {
+        let (mut doc, page_id) = make_test_doc();
+        let style = TableStyle {
+            page_height: Some(842.0),
+            ..Default::default()
+        };
+        let mut table = Table::new()
+            .with_style(style)
+            .with_pixel_widths(vec![150.0, 100.0])
+            .with_page_break_rows(GrowingCarriedRow);
+        for row in 0..60 {
+            table = table.add_row(
+                Row::new(vec![Cell::new(format!("item-{row}")), Cell::new("10.00")])
+                    .with_height(30.0),
+            );
+        }
+
+        let result = doc
+            .draw_table_with_pagination(page_id, table, (50.0, 792.0))
+            .expect("paginated table draw should succeed");
+
+        assert!(result.total_pages >= 2);
+        for &page in &result.page_ids {
+            let extents = page_rect_extents(&doc, page).expect("page should have rects");
+            assert!(extents.min_bottom >= 50.0 - 0.01, "{}", extents.min_bottom);
+        }
+    }
+}
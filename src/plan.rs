@@ -0,0 +1,320 @@
+//! Pagination plans computed without drawing
+
+use crate::Result;
+use crate::drawing::{PageSection, plan_pages};
+use crate::error::TableError;
+use crate::layout::{TableLayout, calculate_layout_for_page};
+use crate::page::{PageBox, Rect};
+use crate::table::Table;
+use std::borrow::Cow;
+use std::ops::Range;
+
+/// A table row, or the part of a split row, placed on a page
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PlannedRow {
+    /// Index of the row in the table
+    pub row: usize,
+    /// Y coordinate of the top of the row
+    pub y: f32,
+    pub height: f32,
+    /// Whether the row continues from the previous page (split rows)
+    pub continued: bool,
+}
+
+/// The part of a paginated table drawn on one page
+#[derive(Debug)]
+pub struct PagePlan {
+    /// Top-left corner of the table on the page
+    pub position: (f32, f32),
+    /// Number of header rows repeated at the top of the page
+    pub repeated_headers: usize,
+    /// Table rows placed on this page for the first time (or continued, for a
+    /// split row), excluding repeated header rows and footer rows
+    pub rows: Range<usize>,
+    /// Every table row drawn on the page, top to bottom, including repeated
+    /// header and footer rows but not generated rows
+    pub placed_rows: Vec<PlannedRow>,
+    /// Area covered by the table on the page
+    pub bounds: Rect,
+    /// Area of the page the table was planned in: within the table's margins
+    /// on the start page, or the region of a continuation page
+    pub region: PageBox,
+    pub(crate) sections: Vec<PageSection>,
+}
+
+impl PagePlan {
+    pub(crate) fn new(
+        position: (f32, f32),
+        repeated_headers: usize,
+        rows: Range<usize>,
+        sections: Vec<PageSection>,
+        region: PageBox,
+        layout: &TableLayout,
+    ) -> Self {
+        let mut y = position.1;
+        let mut placed_rows = Vec::new();
+        for section in &sections {
+            match section {
+                PageSection::Rows(rows) => {
+                    for r in rows {
+                        placed_rows.push(PlannedRow {
+                            row: r.row,
+                            y,
+                            height: r.height,
+                            continued: r.continued,
+                        });
+                        y -= r.height;
+                    }
+                }
+                PageSection::Generated(_) => y -= section.height(),
+            }
+        }
+        let height = position.1 - y;
+
+        Self {
+            position,
+            repeated_headers,
+            rows,
+            placed_rows,
+            bounds: Rect {
+                x: position.0,
+                y,
+                width: layout.total_width,
+                height,
+            },
+            region,
+            sections,
+        }
+    }
+}
+
+/// Pages a table is split into when paginated, computed without drawing.
+///
+/// Draw it with [`TableDrawing::draw_table_plan`](crate::TableDrawing::draw_table_plan).
+#[derive(Debug)]
+pub struct TablePlan<'a> {
+    pub pages: Vec<PagePlan>,
+    /// Whether the table starts on a new page because its first rows do not
+    /// fit on the start page
+    pub starts_on_new_page: bool,
+    /// Position after the table on its last page
+    pub final_position: (f32, f32),
+    /// Indices of rows split across a page boundary
+    pub split_rows: Vec<usize>,
+    /// Visible box of the start page the table was planned on
+    pub(crate) page: PageBox,
+    /// Borrowed while drawing, owned by plans returned to callers
+    pub(crate) layout: Cow<'a, TableLayout>,
+}
+
+impl TablePlan<'_> {
+    /// Number of pages the table is drawn on
+    pub fn total_pages(&self) -> usize {
+        self.pages.len()
+    }
+
+    /// Layout the plan was computed with
+    pub fn layout(&self) -> &TableLayout {
+        &self.layout
+    }
+}
+
+/// Plan the pagination of a table starting at `position` on a page, without a
+/// document.
+///
+/// `page` is the visible page box (see [`PageBox::visible`]); continuation
+/// pages are assumed to have the same box. Tables with a `page_factory` are
+/// rejected, since their continuation pages may have other regions: plan them
+/// with [`plan_pagination_with_regions`]. Tables split into column bands
+/// (`TableStyle::horizontal_pagination`) cannot be planned and fail with
+/// [`TableError::BandedTablePlan`].
+pub fn plan_pagination(
+    table: &Table,
+    page: &PageBox,
+    position: (f32, f32),
+) -> Result<TablePlan<'static>> {
+    if table.page_factory.is_some() {
+        return Err(TableError::LayoutError(
+            "Tables with a page factory must be planned with plan_pagination_with_regions"
+                .to_string(),
+        ));
+    }
+    plan(table, page, position, &mut |_| Ok(None))
+}
+
+/// Plan the pagination of a table whose continuation pages are created by its
+/// `page_factory`.
+///
+/// `continuation_region` returns the region of the continuation page with a
+/// (zero-based) page index of the table, as the factory's
+/// [`ContinuationPage::region`](crate::page::ContinuationPage::region) will.
+/// [`draw_table_plan`](crate::TableDrawing::draw_table_plan) fails when the
+/// pages it draws on do not match the regions planned.
+pub fn plan_pagination_with_regions(
+    table: &Table,
+    page: &PageBox,
+    position: (f32, f32),
+    continuation_region: &dyn Fn(usize) -> PageBox,
+) -> Result<TablePlan<'static>> {
+    plan(table, page, position, &mut |page_index| {
+        Ok(Some(continuation_region(page_index)))
+    })
+}
+
+fn plan(
+    table: &Table,
+    page: &PageBox,
+    position: (f32, f32),
+    new_page: &mut dyn FnMut(usize) -> Result<Option<PageBox>>,
+) -> Result<TablePlan<'static>> {
+    let layout = calculate_layout_for_page(table, page)?;
+    if crate::bands::is_banded(table, &layout, page) {
+        return Err(TableError::BandedTablePlan);
+    }
+    let TablePlan {
+        pages,
+        starts_on_new_page,
+        final_position,
+        split_rows,
+        page,
+        ..
+    } = plan_pages(
+        table,
+        &layout,
+        table.page_break_rows.as_deref(),
+        page,
+        None,
+        position,
+        new_page,
+    )?;
+    Ok(TablePlan {
+        pages,
+        starts_on_new_page,
+        final_position,
+        split_rows,
+        page,
+        layout: Cow::Owned(layout),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TableDrawing;
+    use crate::error::TableError;
+    use crate::style::{HorizontalPagination, TableStyle};
+    use crate::table::{Cell, Row};
+    use crate::test_support::*;
+
+    #[test]
+    fn test_plan_pagination_places_rows_and_repeats_headers() {
+        let style = TableStyle {
+            page_height: Some(400.0),
+            ..Default::default()
+        };
+        let mut table = Table::new()
+            .with_style(style)
+            .with_pixel_widths(vec![100.0])
+            .with_header_rows(1)
+            .add_row(Row::new(vec![Cell::new("Header")]).with_height(20.0));
+        for row in 0..20 {
+            table = table.add_row(Row::new(vec![Cell::new(format!("{row}"))]).with_height(25.0));
+        }
+
+        let plan = plan_pagination(&table, &PageBox::new(300.0, 400.0), (50.0, 350.0)).unwrap();
+
+        // 300pt per page: the header and 11 rows, then the last 9 rows below a repeated header
+        assert_eq!(plan.total_pages(), 2);
+        assert!(!plan.starts_on_new_page);
+        assert_eq!(plan.pages[0].repeated_headers, 0);
+        assert_eq!(plan.pages[0].rows, 0..12);
+        assert_eq!(plan.pages[1].repeated_headers, 1);
+        assert_eq!(plan.pages[1].rows, 12..21);
+
+        let second = &plan.pages[1];
+        assert_eq!(second.position, (50.0, 350.0));
+        assert_eq!(second.placed_rows[0].row, 0);
+        assert_eq!(second.placed_rows[1].row, 12);
+        assert_eq!(second.placed_rows[1].y, 330.0);
+        assert_eq!(plan.final_position, (50.0, 330.0 - 9.0 * 25.0));
+        assert_eq!(second.bounds.y, plan.final_position.1);
+    }
+
+    #[test]
+    fn test_draw_table_plan_matches_direct_pagination() {
+        let table = with_header_row(numbered_rows_table(50));
+        let plan = plan_pagination(&table, &PageBox::new(595.0, 842.0), (50.0, 792.0)).unwrap();
+        assert_eq!(plan.total_pages(), 3);
+
+        let (mut planned_doc, planned_page) = make_test_doc();
+        let planned = planned_doc
+            .draw_table_plan(planned_page, &table, &plan)
+            .unwrap();
+        let (mut direct_doc, direct_page) = make_test_doc();
+        let direct = direct_doc
+            .draw_table_with_pagination(direct_page, table, (50.0, 792.0))
+            .unwrap();
+
+        assert_eq!(planned.total_pages, direct.total_pages);
+        assert_eq!(planned.final_position, direct.final_position);
+        for (&planned_id, &direct_id) in planned.page_ids.iter().zip(&direct.page_ids) {
+            assert_eq!(
+                page_texts(&planned_doc, planned_id),
+                page_texts(&direct_doc, direct_id)
+            );
+        }
+    }
+
+    #[test]
+    fn test_banded_table_cannot_be_planned() {
+        let mut table = numbered_rows_table(10).with_pixel_widths(vec![400.0, 400.0]);
+        for row in &mut table.rows {
+            row.cells.push(Cell::new("wide"));
+        }
+        table.style.horizontal_pagination = Some(HorizontalPagination::new());
+
+        assert!(matches!(
+            plan_pagination(&table, &PageBox::new(595.0, 842.0), (50.0, 792.0)),
+            Err(TableError::BandedTablePlan)
+        ));
+    }
+
+    #[test]
+    fn test_plan_with_page_factory_needs_continuation_regions() {
+        let mut table = numbered_rows_table(40);
+        table.page_factory = Some(std::sync::Arc::new(LetterheadPages {
+            indexes: std::sync::Mutex::new(Vec::new()),
+        }));
+        let page = PageBox::new(595.0, 842.0);
+        assert!(matches!(
+            plan_pagination(&table, &page, (50.0, 792.0)),
+            Err(TableError::LayoutError(_))
+        ));
+
+        let letterhead_region = PageBox {
+            left: 30.0,
+            bottom: 80.0,
+            right: 470.0,
+            top: 600.0,
+            rotation: 0,
+        };
+        let plan =
+            plan_pagination_with_regions(&table, &page, (50.0, 792.0), &|_| letterhead_region)
+                .unwrap();
+        let (mut doc, page_id) = make_test_doc();
+        let planned = doc.draw_table_plan(page_id, &table, &plan).unwrap();
+        let (mut direct_doc, direct_page) = make_test_doc();
+        let direct = direct_doc
+            .draw_table_with_pagination(direct_page, table.clone(), (50.0, 792.0))
+            .unwrap();
+        assert_eq!(planned.final_position, direct.final_position);
+
+        // Regions other than the factory's make drawing fail
+        let plan = plan_pagination_with_regions(&table, &page, (50.0, 792.0), &|_| page).unwrap();
+        let (mut doc, page_id) = make_test_doc();
+        assert!(matches!(
+            doc.draw_table_plan(page_id, &table, &plan),
+            Err(TableError::LayoutError(_))
+        ));
+    }
+}
@@ -0,0 +1,853 @@
+//! Metrics of the standard 14 Type1 fonts, from the Adobe AFM files
+
+use crate::font::FontMetrics;
+use crate::table::{Cell, Table};
+use lopdf::{Dictionary, dictionary};
+
+/// One of the standard 14 Type1 fonts every PDF reader provides.
+///
+/// Implements [`FontMetrics`] with the glyph widths of the Adobe AFM files.
+/// Text is encoded with WinAnsiEncoding, except for `Symbol` and
+/// `ZapfDingbats`, whose built-in encodings are used for ASCII codes.
+/// Characters a font cannot encode are drawn (and measured) as `?`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum StandardFont {
+    Helvetica,
+    HelveticaBold,
+    HelveticaOblique,
+    HelveticaBoldOblique,
+    TimesRoman,
+    TimesBold,
+    TimesItalic,
+    TimesBoldItalic,
+    Courier,
+    CourierBold,
+    CourierOblique,
+    CourierBoldOblique,
+    Symbol,
+    ZapfDingbats,
+}
+
+impl StandardFont {
+    /// Every standard font
+    pub const ALL: [StandardFont; 14] = [
+        StandardFont::Helvetica,
+        StandardFont::HelveticaBold,
+        StandardFont::HelveticaOblique,
+        StandardFont::HelveticaBoldOblique,
+        StandardFont::TimesRoman,
+        StandardFont::TimesBold,
+        StandardFont::TimesItalic,
+        StandardFont::TimesBoldItalic,
+        StandardFont::Courier,
+        StandardFont::CourierBold,
+        StandardFont::CourierOblique,
+        StandardFont::CourierBoldOblique,
+        StandardFont::Symbol,
+        StandardFont::ZapfDingbats,
+    ];
+
+    /// The standard font with the given PostScript name (e.g. "Times-Bold")
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|font| font.base_font() == name)
+    }
+
+    /// PostScript name of the font, used as `BaseFont` in its font dictionary
+    pub fn base_font(self) -> &'static str {
+        match self {
+            StandardFont::Helvetica => "Helvetica",
+            StandardFont::HelveticaBold => "Helvetica-Bold",
+            StandardFont::HelveticaOblique => "Helvetica-Oblique",
+            StandardFont::HelveticaBoldOblique => "Helvetica-BoldOblique",
+            StandardFont::TimesRoman => "Times-Roman",
+            StandardFont::TimesBold => "Times-Bold",
+            StandardFont::TimesItalic => "Times-Italic",
+            StandardFont::TimesBoldItalic => "Times-BoldItalic",
+            StandardFont::Courier => "Courier",
+            StandardFont::CourierBold => "Courier-Bold",
+            StandardFont::CourierOblique => "Courier-Oblique",
+            StandardFont::CourierBoldOblique => "Courier-BoldOblique",
+            StandardFont::Symbol => "Symbol",
+            StandardFont::ZapfDingbats => "ZapfDingbats",
+        }
+    }
+
+    /// The bold variant of the font's family (`Symbol` and `ZapfDingbats`
+    /// have none)
+    pub fn bold(self) -> Self {
+        match self {
+            StandardFont::Helvetica => StandardFont::HelveticaBold,
+            StandardFont::HelveticaOblique => StandardFont::HelveticaBoldOblique,
+            StandardFont::TimesRoman => StandardFont::TimesBold,
+            StandardFont::TimesItalic => StandardFont::TimesBoldItalic,
+            StandardFont::Courier => StandardFont::CourierBold,
+            StandardFont::CourierOblique => StandardFont::CourierBoldOblique,
+            other => other,
+        }
+    }
+
+    /// The italic (or oblique) variant of the font's family (`Symbol` and
+    /// `ZapfDingbats` have none)
+    pub fn italic(self) -> Self {
+        match self {
+            StandardFont::Helvetica => StandardFont::HelveticaOblique,
+            StandardFont::HelveticaBold => StandardFont::HelveticaBoldOblique,
+            StandardFont::TimesRoman => StandardFont::TimesItalic,
+            StandardFont::TimesBold => StandardFont::TimesBoldItalic,
+            StandardFont::Courier => StandardFont::CourierOblique,
+            StandardFont::CourierBold => StandardFont::CourierBoldOblique,
+            other => other,
+        }
+    }
+
+    /// Font a table draws text with for a `font_name` from its style.
+    ///
+    /// Names of the standard 14 fonts select that font; any other name
+    /// selects Helvetica.
+    pub(crate) fn for_font_name(font_name: &str, bold: bool, italic: bool) -> Self {
+        let font = Self::from_name(font_name).unwrap_or(StandardFont::Helvetica);
+        let font = if bold { font.bold() } else { font };
+        if italic { font.italic() } else { font }
+    }
+
+    /// Font a cell's text is drawn with when no embedded font is set, from
+    /// the cell's (or else the table's) `font_name` and the cell's `bold`
+    /// and `italic`
+    pub(crate) fn for_cell(table: &Table, cell: &Cell) -> Self {
+        let style = cell.style.as_ref();
+        let font_name = style
+            .and_then(|s| s.font_name.as_deref())
+            .unwrap_or(&table.style.font_name);
+        Self::for_font_name(
+            font_name,
+            style.is_some_and(|s| s.bold),
+            style.is_some_and(|s| s.italic),
+        )
+    }
+
+    /// Name of the font in page resources, as referenced by table text
+    pub(crate) fn resource_name(self) -> &'static str {
+        match self {
+            StandardFont::Helvetica => "F1",
+            StandardFont::HelveticaBold => "F1-Bold",
+            StandardFont::HelveticaOblique => "F1-Italic",
+            StandardFont::HelveticaBoldOblique => "F1-BoldItalic",
+            StandardFont::Courier => "F2",
+            StandardFont::CourierBold => "F2-Bold",
+            StandardFont::CourierOblique => "F2-Italic",
+            StandardFont::CourierBoldOblique => "F2-BoldItalic",
+            StandardFont::TimesRoman => "F3",
+            StandardFont::TimesBold => "F3-Bold",
+            StandardFont::TimesItalic => "F3-Italic",
+            StandardFont::TimesBoldItalic => "F3-BoldItalic",
+            StandardFont::Symbol => "F4",
+            StandardFont::ZapfDingbats => "F5",
+        }
+    }
+
+    /// Type1 font dictionary of the font, with WinAnsiEncoding for the
+    /// Latin fonts
+    pub fn font_dictionary(self) -> Dictionary {
+        let mut font = dictionary! {
+            "Type" => "Font",
+            "Subtype" => "Type1",
+            "BaseFont" => self.base_font(),
+        };
+        if !self.is_symbolic() {
+            font.set("Encoding", "WinAnsiEncoding");
+        }
+        font
+    }
+
+    /// The font as a `&'static dyn FontMetrics`
+    pub(crate) fn metrics(self) -> &'static dyn FontMetrics {
+        static FONTS: [StandardFont; 14] = StandardFont::ALL;
+        &FONTS[self as usize]
+    }
+
+    fn widths(self) -> &'static FontWidths {
+        match self {
+            StandardFont::Helvetica | StandardFont::HelveticaOblique => &HELVETICA,
+            StandardFont::HelveticaBold | StandardFont::HelveticaBoldOblique => &HELVETICA_BOLD,
+            StandardFont::TimesRoman => &TIMES_ROMAN,
+            StandardFont::TimesBold => &TIMES_BOLD,
+            StandardFont::TimesItalic => &TIMES_ITALIC,
+            StandardFont::TimesBoldItalic => &TIMES_BOLD_ITALIC,
+            StandardFont::Courier
+            | StandardFont::CourierBold
+            | StandardFont::CourierOblique
+            | StandardFont::CourierBoldOblique => &COURIER,
+            StandardFont::Symbol => &SYMBOL,
+            StandardFont::ZapfDingbats => &ZAPF_DINGBATS,
+        }
+    }
+
+    /// Whether the font uses its built-in encoding rather than WinAnsiEncoding
+    pub(crate) fn is_symbolic(self) -> bool {
+        matches!(self, StandardFont::Symbol | StandardFont::ZapfDingbats)
+    }
+
+    /// Byte code of a character in the font's encoding
+    fn code(self, ch: char) -> Option<u8> {
+        if self.is_symbolic() {
+            (' '..='~').contains(&ch).then_some(ch as u8)
+        } else {
+            win_ansi_code(ch)
+        }
+    }
+
+    /// Width of a character in thousandths of the font size
+    fn glyph_width(self, ch: char) -> u16 {
+        let widths = self.widths();
+        let Some(code) = self.code(ch) else {
+            return widths.ascii[(b'?' - b' ') as usize];
+        };
+        if (b' '..=b'~').contains(&code) {
+            return widths.ascii[(code - b' ') as usize];
+        }
+        if let Some(&(_, width)) = widths.extra.iter().find(|(extra, _)| *extra == ch) {
+            return width;
+        }
+        match base_letter(ch) {
+            Some(base) => widths.ascii[(base as u8 - b' ') as usize],
+            None => widths.default,
+        }
+    }
+}
+
+impl FontMetrics for StandardFont {
+    fn char_width(&self, ch: char, font_size: f32) -> f32 {
+        self.glyph_width(ch) as f32 / 1000.0 * font_size
+    }
+
+    fn text_width(&self, text: &str, font_size: f32) -> f32 {
+        let units: u32 = text.chars().map(|ch| self.glyph_width(ch) as u32).sum();
+        units as f32 / 1000.0 * font_size
+    }
+
+    fn encode_text(&self, text: &str) -> Vec<u8> {
+        text.chars()
+            .map(|ch| self.code(ch).unwrap_or(b'?'))
+            .collect()
+    }
+}
+
+/// Glyph widths of a font, in thousandths of the font size
+struct FontWidths {
+    /// Widths of the codes 32 to 126
+    ascii: [u16; 95],
+    /// Widths of other characters, besides accented letters
+    extra: &'static [(char, u16)],
+    /// Width of encodable characters without a known width
+    default: u16,
+}
+
+/// Code of a character in WinAnsiEncoding
+fn win_ansi_code(ch: char) -> Option<u8> {
+    match ch as u32 {
+        0x20..=0x7E | 0xA0..=0xFF => Some(ch as u8),
+        _ => WIN_ANSI_HIGH
+            .iter()
+            .position(|&high| high == ch)
+            .map(|index| 0x80 + index as u8),
+    }
+}
+
+/// Characters of the WinAnsiEncoding codes 0x80 to 0x9F (`\0` where unused)
+const WIN_ANSI_HIGH: [char; 32] = [
+    '€', '\0', '‚', 'ƒ', '„', '…', '†', '‡', 'ˆ', '‰', 'Š', '‹', 'Œ', '\0', 'Ž', '\0', '\0', '‘',
+    '’', '“', '”', '•', '–', '—', '˜', '™', 'š', '›', 'œ', '\0', 'ž', 'Ÿ',
+];
+
+/// Unaccented letter of an accented Latin letter, which has the same width
+/// in the standard fonts
+fn base_letter(ch: char) -> Option<char> {
+    Some(match ch {
+        'À'..='Å' => 'A',
+        'Ç' => 'C',
+        'È'..='Ë' => 'E',
+        'Ì'..='Ï' => 'I',
+        'Ð' => 'D',
+        'Ñ' => 'N',
+        'Ò'..='Ö' => 'O',
+        'Ù'..='Ü' => 'U',
+        'Ý' | 'Ÿ' => 'Y',
+        'Þ' => 'P',
+        'Š' => 'S',
+        'Ž' => 'Z',
+        'à'..='å' => 'a',
+        'ç' => 'c',
+        'è'..='ë' => 'e',
+        'ì'..='ï' => 'i',
+        'ð' => 'o',
+        'ñ' => 'n',
+        'ò'..='ö' => 'o',
+        'ù'..='ü' => 'u',
+        'ý' | 'ÿ' => 'y',
+        'þ' => 'p',
+        'š' => 's',
+        'ž' => 'z',
+        _ => return None,
+    })
+}
+
+const HELVETICA: FontWidths = FontWidths {
+    ascii: [
+        278, 278, 355, 556, 556, 889, 667, 191, 333, 333, 389, 584, 278, 333, 278,
+        278, // space - /
+        556, 556, 556, 556, 556, 556, 556, 556, 556, 556, // 0 - 9
+        278, 278, 584, 584, 584, 556, 1015, // : - @
+        667, 667, 722, 722, 667, 611, 778, 722, 278, 500, 667, 556, 833, // A - M
+        722, 778, 667, 778, 722, 667, 611, 722, 667, 944, 667, 667, 611, // N - Z
+        278, 278, 278, 469, 556, 333, // [ - `
+        556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500, 222, 833, // a - m
+        556, 556, 556, 556, 333, 500, 278, 556, 500, 722, 500, 500, 500, // n - z
+        334, 260, 334, 584, // { - ~
+    ],
+    extra: &[
+        ('\u{a0}', 278),
+        ('¡', 333),
+        ('¢', 556),
+        ('£', 556),
+        ('¥', 556),
+        ('§', 556),
+        ('©', 737),
+        ('«', 556),
+        ('®', 737),
+        ('°', 400),
+        ('±', 584),
+        ('µ', 556),
+        ('¶', 537),
+        ('·', 278),
+        ('»', 556),
+        ('¼', 834),
+        ('½', 834),
+        ('¾', 834),
+        ('¿', 611),
+        ('Æ', 1000),
+        ('×', 584),
+        ('Ø', 778),
+        ('ß', 611),
+        ('æ', 889),
+        ('ì', 278),
+        ('í', 278),
+        ('î', 278),
+        ('ï', 278),
+        ('÷', 584),
+        ('ø', 611),
+        ('€', 556),
+        ('…', 1000),
+        ('‘', 222),
+        ('’', 222),
+        ('“', 333),
+        ('”', 333),
+        ('•', 350),
+        ('–', 556),
+        ('—', 1000),
+        ('™', 1000),
+    ],
+    default: 556,
+};
+
+const HELVETICA_BOLD: FontWidths = FontWidths {
+    ascii: [
+        278, 333, 474, 556, 556, 889, 722, 238, 333, 333, 389, 584, 278, 333, 278,
+        278, // space - /
+        556, 556, 556, 556, 556, 556, 556, 556, 556, 556, // 0 - 9
+        333, 333, 584, 584, 584, 611, 975, // : - @
+        722, 722, 722, 722, 667, 611, 778, 722, 278, 556, 722, 611, 833, // A - M
+        722, 778, 667, 778, 722, 667, 611, 722, 667, 944, 667, 667, 611, // N - Z
+        333, 278, 333, 584, 556, 333, // [ - `
+        556, 611, 556, 611, 556, 333, 611, 611, 278, 278, 556, 278, 889, // a - m
+        611, 611, 611, 611, 389, 556, 333, 611, 556, 778, 556, 556, 500, // n - z
+        389, 280, 389, 584, // { - ~
+    ],
+    extra: &[
+        ('\u{a0}', 278),
+        ('¡', 333),
+        ('¢', 556),
+        ('£', 556),
+        ('¥', 556),
+        ('§', 556),
+        ('©', 737),
+        ('«', 556),
+        ('®', 737),
+        ('°', 400),
+        ('±', 584),
+        ('µ', 611),
+        ('¶', 556),
+        ('·', 278),
+        ('»', 556),
+        ('¼', 834),
+        ('½', 834),
+        ('¾', 834),
+        ('¿', 611),
+        ('Æ', 1000),
+        ('×', 584),
+        ('Ø', 778),
+        ('ß', 611),
+        ('æ', 889),
+        ('÷', 584),
+        ('ø', 611),
+        ('€', 556),
+        ('…', 1000),
+        ('‘', 278),
+        ('’', 278),
+        ('“', 500),
+        ('”', 500),
+        ('•', 350),
+        ('–', 556),
+        ('—', 1000),
+        ('™', 1000),
+    ],
+    default: 556,
+};
+
+const TIMES_ROMAN: FontWidths = FontWidths {
+    ascii: [
+        250, 333, 408, 500, 500, 833, 778, 180, 333, 333, 500, 564, 250, 333, 250,
+        278, // space - /
+        500, 500, 500, 500, 500, 500, 500, 500, 500, 500, // 0 - 9
+        278, 278, 564, 564, 564, 444, 921, // : - @
+        722, 667, 667, 722, 611, 556, 722, 722, 333, 389, 722, 611, 889, // A - M
+        722, 722, 556, 722, 667, 556, 611, 722, 722, 944, 722, 722, 611, // N - Z
+        333, 278, 333, 469, 500, 333, // [ - `
+        444, 500, 444, 500, 444, 333, 500, 500, 278, 278, 500, 278, 778, // a - m
+        500, 500, 500, 500, 333, 389, 278, 500, 500, 722, 500, 500, 444, // n - z
+        480, 200, 480, 541, // { - ~
+    ],
+    extra: &[
+        ('\u{a0}', 250),
+        ('¡', 333),
+        ('¢', 500),
+        ('£', 500),
+        ('¥', 500),
+        ('§', 500),
+        ('©', 760),
+        ('«', 500),
+        ('®', 760),
+        ('°', 400),
+        ('±', 564),
+        ('µ', 500),
+        ('¶', 453),
+        ('·', 250),
+        ('»', 500),
+        ('¼', 750),
+        ('½', 750),
+        ('¾', 750),
+        ('¿', 444),
+        ('Æ', 889),
+        ('×', 564),
+        ('Ø', 722),
+        ('ß', 500),
+        ('æ', 667),
+        ('÷', 564),
+        ('ø', 500),
+        ('€', 500),
+        ('…', 1000),
+        ('‘', 333),
+        ('’', 333),
+        ('“', 444),
+        ('”', 444),
+        ('•', 350),
+        ('–', 500),
+        ('—', 1000),
+        ('™', 980),
+    ],
+    default: 500,
+};
+
+const TIMES_BOLD: FontWidths = FontWidths {
+    ascii: [
+        250, 333, 555, 500, 500, 1000, 833, 278, 333, 333, 500, 570, 250, 333, 250,
+        278, // space - /
+        500, 500, 500, 500, 500, 500, 500, 500, 500, 500, // 0 - 9
+        333, 333, 570, 570, 570, 500, 930, // : - @
+        722, 667, 722, 722, 667, 611, 778, 778, 389, 500, 778, 667, 944, // A - M
+        722, 778, 611, 778, 722, 556, 667, 722, 722, 1000, 722, 722, 667, // N - Z
+        333, 278, 333, 581, 500, 333, // [ - `
+        500, 556, 444, 556, 444, 333, 500, 556, 278, 333, 556, 278, 833, // a - m
+        556, 500, 556, 556, 444, 389, 333, 556, 500, 722, 500, 500, 444, // n - z
+        394, 220, 394, 520, // { - ~
+    ],
+    extra: &[
+        ('\u{a0}', 250),
+        ('¡', 333),
+        ('¢', 500),
+        ('£', 500),
+        ('¥', 500),
+        ('§', 500),
+        ('©', 747),
+        ('«', 500),
+        ('®', 747),
+        ('°', 400),
+        ('±', 570),
+        ('µ', 556),
+        ('¶', 540),
+        ('·', 250),
+        ('»', 500),
+        ('¼', 750),
+        ('½', 750),
+        ('¾', 750),
+        ('¿', 500),
+        ('Æ', 1000),
+        ('×', 570),
+        ('Ø', 778),
+        ('ß', 556),
+        ('æ', 722),
+        ('÷', 570),
+        ('ø', 500),
+        ('€', 500),
+        ('…', 1000),
+        ('‘', 333),
+        ('’', 333),
+        ('“', 500),
+        ('”', 500),
+        ('•', 350),
+        ('–', 500),
+        ('—', 1000),
+        ('™', 1000),
+    ],
+    default: 500,
+};
+
+const TIMES_ITALIC: FontWidths = FontWidths {
+    ascii: [
+        250, 333, 420, 500, 500, 833, 778, 214, 333, 333, 500, 675, 250, 333, 250,
+        278, // space - /
+        500, 500, 500, 500, 500, 500, 500, 500, 500, 500, // 0 - 9
+        333, 333, 675, 675, 675, 500, 920, // : - @
+        611, 611, 667, 722, 611, 611, 722, 722, 333, 444, 667, 556, 833, // A - M
+        667, 722, 611, 722, 611, 500, 556, 722, 611, 833, 611, 556, 556, // N - Z
+        389, 278, 389, 422, 500, 333, // [ - `
+        500, 500, 444, 500, 444, 278, 500, 500, 278, 278, 444, 278, 722, // a - m
+        500, 500, 500, 500, 389, 389, 278, 500, 444, 667, 444, 444, 389, // n - z
+        400, 275, 400, 541, // { - ~
+    ],
+    extra: &[
+        ('\u{a0}', 250),
+        ('¡', 389),
+        ('¢', 500),
+        ('£', 500),
+        ('¥', 500),
+        ('§', 500),
+        ('©', 760),
+        ('«', 500),
+        ('®', 760),
+        ('°', 400),
+        ('±', 675),
+        ('µ', 500),
+        ('¶', 523),
+        ('·', 250),
+        ('»', 500),
+        ('¼', 750),
+        ('½', 750),
+        ('¾', 750),
+        ('¿', 500),
+        ('Æ', 889),
+        ('×', 675),
+        ('Ø', 722),
+        ('ß', 500),
+        ('æ', 667),
+        ('÷', 675),
+        ('ø', 500),
+        ('€', 500),
+        ('…', 889),
+        ('‘', 333),
+        ('’', 333),
+        ('“', 556),
+        ('”', 556),
+        ('•', 350),
+        ('–', 500),
+        ('—', 889),
+        ('™', 980),
+    ],
+    default: 500,
+};
+
+const TIMES_BOLD_ITALIC: FontWidths = FontWidths {
+    ascii: [
+        250, 389, 555, 500, 500, 833, 778, 278, 333, 333, 500, 570, 250, 333, 250,
+        278, // space - /
+        500, 500, 500, 500, 500, 500, 500, 500, 500, 500, // 0 - 9
+        333, 333, 570, 570, 570, 500, 832, // : - @
+        667, 667, 667, 722, 667, 667, 722, 778, 389, 500, 667, 611, 889, // A - M
+        722, 722, 611, 722, 667, 556, 611, 722, 667, 889, 667, 611, 611, // N - Z
+        333, 278, 333, 570, 500, 333, // [ - `
+        500, 500, 444, 500, 444, 333, 500, 556, 278, 278, 500, 278, 778, // a - m
+        556, 500, 500, 500, 389, 389, 278, 556, 444, 667, 500, 444, 389, // n - z
+        348, 220, 348, 570, // { - ~
+    ],
+    extra: &[
+        ('\u{a0}', 250),
+        ('¡', 389),
+        ('¢', 500),
+        ('£', 500),
+        ('¥', 500),
+        ('§', 500),
+        ('©', 747),
+        ('«', 500),
+        ('®', 747),
+        ('°', 400),
+        ('±', 570),
+        ('µ', 576),
+        ('¶', 500),
+        ('·', 250),
+        ('»', 500),
+        ('¼', 750),
+        ('½', 750),
+        ('¾', 750),
+        ('¿', 500),
+        ('Æ', 944),
+        ('×', 570),
+        ('Ø', 722),
+        ('ß', 500),
+        ('æ', 722),
+        ('÷', 570),
+        ('ø', 500),
+        ('€', 500),
+        ('…', 1000),
+        ('‘', 333),
+        ('’', 333),
+        ('“', 500),
+        ('”', 500),
+        ('•', 350),
+        ('–', 500),
+        ('—', 1000),
+        ('™', 1000),
+    ],
+    default: 500,
+};
+
+const COURIER: FontWidths = FontWidths {
+    ascii: [600; 95],
+    extra: &[],
+    default: 600,
+};
+
+const SYMBOL: FontWidths = FontWidths {
+    ascii: [
+        250, 333, 713, 500, 549, 833, 778, 439, 333, 333, 500, 549, 250, 549, 250,
+        278, // space - /
+        500, 500, 500, 500, 500, 500, 500, 500, 500, 500, // 0 - 9
+        278, 278, 549, 549, 549, 444, 549, // : - @
+        722, 667, 722, 612, 611, 763, 603, 722, 333, 631, 722, 686, 889, // A - M
+        722, 722, 768, 741, 556, 592, 611, 690, 439, 768, 645, 795, 611, // N - Z
+        333, 863, 333, 658, 500, 500, // [ - `
+        631, 549, 549, 494, 439, 521, 411, 603, 329, 603, 549, 549, 576, // a - m
+        521, 549, 549, 521, 549, 603, 439, 576, 713, 686, 493, 686, 494, // n - z
+        480, 200, 480, 549, // { - ~
+    ],
+    extra: &[],
+    default: 500,
+};
+
+const ZAPF_DINGBATS: FontWidths = FontWidths {
+    ascii: [
+        278, 974, 961, 974, 980, 719, 789, 790, 791, 690, 960, 939, 549, 855, 911,
+        933, // space - /
+        911, 945, 974, 755, 846, 762, 761, 571, 677, 763, // 0 - 9
+        760, 759, 754, 494, 552, 537, 577, // : - @
+        692, 786, 788, 788, 790, 793, 794, 816, 823, 789, 841, 823, 833, // A - M
+        816, 831, 923, 744, 723, 749, 790, 792, 695, 776, 768, 792, 759, // N - Z
+        707, 708, 682, 701, 826, 815, // [ - `
+        789, 789, 707, 687, 696, 689, 786, 787, 713, 791, 785, 791, 873, // a - m
+        761, 762, 762, 759, 759, 892, 892, 788, 784, 438, 138, 277, 415, // n - z
+        392, 392, 668, 668, // { - ~
+    ],
+    extra: &[],
+    default: 500,
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TableDrawing;
+    use crate::page_break::RunningSubtotals;
+    use crate::style::{CellStyle, TableStyle};
+    use crate::table::{Cell, PageFill, Row, Table};
+    use crate::test_support::*;
+    use std::collections::BTreeSet;
+
+    #[test]
+    fn test_afm_widths() {
+        assert_eq!(StandardFont::Helvetica.text_width("Hello", 10.0), 22.78);
+        assert_eq!(StandardFont::HelveticaBold.char_width('W', 1000.0), 944.0);
+        assert_eq!(StandardFont::TimesRoman.char_width('m', 1000.0), 778.0);
+        assert_eq!(StandardFont::CourierBold.text_width("iiii", 10.0), 24.0);
+        // Accented letters are as wide as their base letter
+        assert_eq!(
+            StandardFont::TimesItalic.char_width('é', 10.0),
+            StandardFont::TimesItalic.char_width('e', 10.0)
+        );
+        assert_eq!(StandardFont::Helvetica.char_width('€', 1000.0), 556.0);
+    }
+
+    #[test]
+    fn test_font_selection_by_name() {
+        assert_eq!(
+            StandardFont::for_font_name("Helvetica", true, false),
+            StandardFont::HelveticaBold
+        );
+        assert_eq!(
+            StandardFont::for_font_name("Times-Bold", false, false),
+            StandardFont::TimesBold
+        );
+        assert_eq!(
+            StandardFont::for_font_name("Courier-New", false, false),
+            StandardFont::Helvetica
+        );
+        assert_eq!(
+            StandardFont::for_font_name("Times New Roman", true, false),
+            StandardFont::HelveticaBold
+        );
+        assert_eq!(
+            StandardFont::for_font_name("Arial", false, false),
+            StandardFont::Helvetica
+        );
+        assert_eq!(
+            StandardFont::for_font_name("Times-Roman", true, true),
+            StandardFont::TimesBoldItalic
+        );
+        assert_eq!(
+            StandardFont::for_font_name("Courier", false, true),
+            StandardFont::CourierOblique
+        );
+        assert_eq!(
+            StandardFont::for_font_name("Helvetica", true, true).resource_name(),
+            "F1-BoldItalic"
+        );
+        assert_eq!(StandardFont::Symbol.bold(), StandardFont::Symbol);
+        assert_eq!(
+            StandardFont::ZapfDingbats.italic(),
+            StandardFont::ZapfDingbats
+        );
+        for font in StandardFont::ALL {
+            assert_eq!(StandardFont::from_name(font.base_font()), Some(font));
+        }
+    }
+
+    #[test]
+    fn test_win_ansi_encoding() {
+        let font = StandardFont::Helvetica;
+        assert_eq!(font.encode_text("Aé€—"), vec![b'A', 0xE9, 0x80, 0x97]);
+        // Characters outside the encoding are drawn and measured as '?'
+        assert_eq!(font.encode_text("Ω"), vec![b'?']);
+        assert_eq!(font.char_width('Ω', 10.0), font.char_width('?', 10.0));
+        assert_eq!(StandardFont::Symbol.encode_text("a€"), vec![b'a', b'?']);
+    }
+
+    #[test]
+    fn test_standard_fonts_added_to_every_drawn_page() {
+        let (mut doc, page_id) = make_test_doc();
+        let user_font = doc.get_page_fonts(page_id).unwrap()[b"F1".as_slice()].clone();
+        let courier = CellStyle {
+            font_name: Some("Courier".to_string()),
+            ..Default::default()
+        };
+        let table = numbered_rows_table(40)
+            .add_row(Row::new(vec![Cell::new("Courier").with_style(courier)]))
+            .add_row(Row::new(vec![Cell::new("Bold italic").bold().italic()]));
+
+        let result = doc
+            .draw_table_with_pagination(page_id, table, (50.0, 792.0))
+            .unwrap();
+        assert_eq!(result.total_pages, 2);
+
+        for &page in &result.page_ids {
+            let fonts = doc.get_page_fonts(page).unwrap();
+            for (name, base_font) in [
+                ("F1-Bold", "Helvetica-Bold"),
+                ("F1-BoldItalic", "Helvetica-BoldOblique"),
+                ("F2", "Courier"),
+            ] {
+                let font = fonts[name.as_bytes()];
+                assert_eq!(
+                    font.get(b"BaseFont").unwrap().as_name().unwrap(),
+                    base_font.as_bytes()
+                );
+                assert_eq!(
+                    font.get(b"Encoding").unwrap().as_name().unwrap(),
+                    b"WinAnsiEncoding"
+                );
+            }
+            assert!(!fonts.contains_key(b"F3".as_slice()));
+        }
+        // A font the page already has under a standard name is kept
+        let fonts = doc.get_page_fonts(page_id).unwrap();
+        assert_eq!(*fonts[b"F1".as_slice()], user_font);
+
+        // A fresh page without resources gets every font it references
+        let (mut doc, page_id) = make_test_doc();
+        doc.get_dictionary_mut(page_id)
+            .unwrap()
+            .remove(b"Resources");
+        let table = Table::new().add_row(Row::new(vec![Cell::new("A")]));
+        doc.draw_table(page_id, table, (50.0, 750.0)).unwrap();
+        let fonts = doc.get_page_fonts(page_id).unwrap();
+        assert_eq!(
+            fonts[b"F1".as_slice()]
+                .get(b"BaseFont")
+                .unwrap()
+                .as_name()
+                .unwrap(),
+            b"Helvetica"
+        );
+    }
+
+    #[test]
+    fn test_fonts_of_generated_rows_added_to_every_drawn_page() {
+        let (mut doc, page_id) = make_test_doc();
+        let style = TableStyle {
+            font_name: "Courier".to_string(),
+            page_height: Some(842.0),
+            ..Default::default()
+        };
+        let subtotal_style = CellStyle {
+            bold: true,
+            ..Default::default()
+        };
+        let filler = Row::new(vec![
+            Cell::new("-").with_style(CellStyle {
+                font_name: Some("Times-Roman".to_string()),
+                ..Default::default()
+            }),
+            Cell::new(""),
+        ]);
+        let mut table = Table::new()
+            .with_style(style)
+            .with_pixel_widths(vec![150.0, 100.0])
+            .with_page_break_rows(RunningSubtotals::new(vec![1]).with_cell_style(subtotal_style))
+            .with_page_fill(PageFill::FillerRows(filler.with_height(30.0)));
+        for row in 0..40 {
+            table = table.add_row(
+                Row::new(vec![Cell::new(format!("item-{row}")), Cell::new("10.00")])
+                    .with_height(30.0),
+            );
+        }
+
+        let result = doc
+            .draw_table_with_pagination(page_id, table, (50.0, 792.0))
+            .unwrap();
+        assert_eq!(result.total_pages, 2);
+
+        let mut drawn = BTreeSet::new();
+        for &page in &result.page_ids {
+            let fonts = doc.get_page_fonts(page).unwrap();
+            for op in page_content_operations(&doc, page) {
+                if op.operator == "Tf" {
+                    let name = op.operands[0].as_name().unwrap().to_vec();
+                    assert!(
+                        fonts.contains_key(&name),
+                        "{} not registered",
+                        String::from_utf8_lossy(&name)
+                    );
+                    drawn.insert(name);
+                }
+            }
+        }
+        for name in ["F2", "F2-Bold", "F3"] {
+            assert!(drawn.contains(name.as_bytes()), "{name} not drawn");
+        }
+    }
+}
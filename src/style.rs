@@ -128,6 +128,52 @@ impl Default for Padding {
     }
 }
 
+/// Order in which the column bands of a wide table are placed on pages
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum BandOrder {
+    /// Every page of the first band, then every page of the next band
+    #[default]
+    DownThenAcross,
+    /// The first page of every band, then the second page of every band
+    AcrossThenDown,
+}
+
+/// Splitting of tables wider than the page into column bands when paginating
+#[derive(Debug, Clone, Default)]
+pub struct HorizontalPagination {
+    /// Maximum width of one band, including its key columns. Defaults to the
+    /// page's visible width between the table's left and right margins.
+    pub max_width: Option<f32>,
+    /// Columns repeated at the left of every band (e.g. an ID column)
+    pub key_columns: Vec<usize>,
+    pub order: BandOrder,
+}
+
+impl HorizontalPagination {
+    /// Split columns into bands that fit between the page margins
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Split columns into bands no wider than `max_width` instead
+    pub fn with_max_width(mut self, max_width: f32) -> Self {
+        self.max_width = Some(max_width);
+        self
+    }
+
+    /// Set the columns repeated on every band
+    pub fn with_key_columns(mut self, columns: Vec<usize>) -> Self {
+        self.key_columns = columns;
+        self
+    }
+
+    /// Set the order in which bands are placed on pages
+    pub fn with_order(mut self, order: BandOrder) -> Self {
+        self.order = order;
+        self
+    }
+}
+
 /// Styling for the entire table
 #[derive(Debug, Clone)]
 pub struct TableStyle {
@@ -139,14 +185,31 @@ pub struct TableStyle {
     /// Default font for the table
     pub font_name: String,
     pub default_font_size: f32,
-    /// Page height for pagination (if None, uses standard A4: 842 points)
+    /// Page height for pagination (if None, uses the height of the visible page)
     pub page_height: Option<f32>,
     /// Top margin for pages
     pub top_margin: f32,
     /// Bottom margin for pages
     pub bottom_margin: f32,
+    /// Left margin for pages, limiting the width of tables without a `total_width`
+    pub left_margin: f32,
+    /// Right margin for pages
+    pub right_margin: f32,
+    /// Horizontal placement of the table between the left and right margins.
+    /// When None, the table starts at the x coordinate it is drawn at.
+    pub horizontal_alignment: Option<Alignment>,
     /// Whether to repeat header rows on new pages
     pub repeat_headers: bool,
+    /// Whether rows crossing the bottom margin are split when paginating.
+    /// When enabled, the wrapped text lines of each cell that fit are drawn on
+    /// the current page and the rest continue on the next page below any
+    /// repeated headers. When disabled, rows move to the next page whole.
+    pub split_rows: bool,
+    /// When set, paginated tables wider than the page (or its `max_width`) are
+    /// split into column bands drawn on separate pages. Each band is paginated
+    /// vertically like a standalone table, with the same row heights and
+    /// header repetition.
+    pub horizontal_pagination: Option<HorizontalPagination>,
     /// PDF resource name for an embedded font (e.g., "EF0").
     /// When set together with `font_metrics` on the Table, text will be
     /// encoded as glyph IDs and rendered using this font resource.
@@ -154,6 +217,12 @@ pub struct TableStyle {
     /// PDF resource name for a bold embedded font (e.g., "EF0B").
     /// When set and a cell is marked bold, this font resource is used.
     pub embedded_font_resource_name_bold: Option<String>,
+    /// PDF resource name for an italic embedded font (e.g., "EF0I").
+    /// When set and a cell is marked italic, this font resource is used.
+    pub embedded_font_resource_name_italic: Option<String>,
+    /// PDF resource name for a bold italic embedded font (e.g., "EF0BI").
+    /// When set and a cell is marked bold and italic, this font resource is used.
+    pub embedded_font_resource_name_bold_italic: Option<String>,
 }
 
 impl Default for TableStyle {
@@ -166,16 +235,59 @@ impl Default for TableStyle {
             padding: Padding::default(),
             font_name: "Helvetica".to_string(),
             default_font_size: 10.0,
-            page_height: None, // Will default to A4 (842 points)
+            page_height: None, // Will default to the visible page height
             top_margin: DEFAULT_MARGIN,
             bottom_margin: DEFAULT_MARGIN,
+            left_margin: DEFAULT_MARGIN,
+            right_margin: DEFAULT_MARGIN,
+            horizontal_alignment: None,
             repeat_headers: true,
+            split_rows: false,
+            horizontal_pagination: None,
             embedded_font_resource_name: None,
             embedded_font_resource_name_bold: None,
+            embedded_font_resource_name_italic: None,
+            embedded_font_resource_name_bold_italic: None,
         }
     }
 }
 
+impl TableStyle {
+    /// Embedded font resource name for bold and/or italic text (see
+    /// [`font_variant`])
+    pub(crate) fn embedded_font_resource_name_for(&self, bold: bool, italic: bool) -> Option<&str> {
+        font_variant(
+            bold,
+            italic,
+            [
+                self.embedded_font_resource_name.as_deref(),
+                self.embedded_font_resource_name_bold.as_deref(),
+                self.embedded_font_resource_name_italic.as_deref(),
+                self.embedded_font_resource_name_bold_italic.as_deref(),
+            ],
+        )
+    }
+}
+
+/// Pick the variant of a font for bold and/or italic text from its regular,
+/// bold, italic and bold italic variants.
+///
+/// Bold italic text falls back to the bold, then the italic variant, and
+/// every variant falls back to the regular one.
+pub(crate) fn font_variant<T>(
+    bold: bool,
+    italic: bool,
+    [regular, bold_variant, italic_variant, bold_italic_variant]: [Option<T>; 4],
+) -> Option<T> {
+    let variant = match (bold, italic) {
+        (false, false) => None,
+        (true, false) => bold_variant,
+        (false, true) => italic_variant,
+        (true, true) => bold_italic_variant.or(bold_variant).or(italic_variant),
+    };
+    variant.or(regular)
+}
+
 /// Styling for a row
 #[derive(Debug, Clone)]
 pub struct RowStyle {
@@ -203,7 +315,8 @@ pub struct CellStyle {
     pub text_color: Color,
     pub font_size: Option<f32>,
     /// Font name for this cell. If None, inherits from table's font_name.
-    /// Supported fonts: "Helvetica", "Courier", "Times-Roman" (and their bold variants)
+    /// Any of the standard 14 fonts, by exact name; any other name selects
+    /// Helvetica. `bold` and `italic` select the family's variants.
     pub font_name: Option<String>,
     pub bold: bool,
     pub italic: bool,
@@ -1,9 +1,17 @@
 //! Core table structures
 
 use crate::Result;
+use crate::constants::MIN_COLUMN_WIDTH;
 use crate::error::TableError;
 use crate::font::FontMetrics;
-use crate::style::{CellStyle, RowStyle, TableStyle};
+#[cfg(feature = "ttf-parser")]
+use crate::font_embedding::EmbeddedFont;
+use crate::grid::CellGrid;
+use crate::page::{PageDecorator, PageFactory};
+use crate::page_break::PageBreakRows;
+use crate::style::{CellStyle, RowStyle, TableStyle, font_variant};
+use lopdf::ObjectId;
+use std::ops::Range;
 use std::sync::Arc;
 use tracing::trace;
 
@@ -155,6 +163,27 @@ pub enum ColumnWidth {
     Percentage(f32),
     /// Automatically calculate based on content
     Auto,
+    /// Share of the width left over by the other columns, by weight
+    /// (like CSS `fr` units); never narrower than the column's content allows
+    Fraction(f32),
+    /// Width clamped between `min` and `max`, starting from `preferred`
+    /// (or the column's content width when `None`). `preferred` must lie
+    /// within `min..=max`.
+    MinMax {
+        min: f32,
+        max: f32,
+        preferred: Option<f32>,
+    },
+}
+
+/// How the last page of a paginated table is filled down to the bottom margin
+/// (or the footer rows), e.g. for ledger-style forms
+#[derive(Debug, Clone)]
+pub enum PageFill {
+    /// Add copies of a template row (typically empty cells) while they fit
+    FillerRows(Row),
+    /// Stretch the last data row over the remaining height
+    StretchLastRow,
 }
 
 /// Represents a table with rows and styling
@@ -164,16 +193,52 @@ pub struct Table {
     pub style: TableStyle,
     /// Column width specifications
     pub column_widths: Option<Vec<ColumnWidth>>,
-    /// Total table width (if None, auto-calculate based on content)
+    /// Total table width (if None, auto-calculate based on content).
+    ///
+    /// Layout fails with [`TableError::InvalidTable`](crate::TableError::InvalidTable)
+    /// when the fixed widths and minimums of `column_widths` need more than
+    /// this width.
     pub total_width: Option<f32>,
     /// Number of header rows to repeat on each page when paginating
     pub header_rows: usize,
+    /// Number of trailing rows drawn as a footer at the bottom of every page
+    /// when paginating (e.g. "continued on next page" or column legends)
+    pub footer_rows: usize,
+    /// Number of rows immediately before the footer rows that are drawn only
+    /// once, at the end of the table, and kept together (e.g. grand totals)
+    pub final_footer_rows: usize,
+    /// Ranges of rows that pagination never separates (e.g. an order and its
+    /// line items), unless a group alone is taller than a page
+    pub keep_together: Vec<Range<usize>>,
+    /// Maximum number of data rows (excluding header and footer rows) placed
+    /// on one page when paginating, regardless of the remaining page height
+    pub max_rows_per_page: Option<usize>,
+    /// Fill the remaining space of the last page when paginating
+    pub page_fill: Option<PageFill>,
     /// Font metrics for accurate text measurement and Unicode encoding.
     /// When set, enables font-aware text wrapping and glyph ID encoding.
     pub font_metrics: Option<Arc<dyn FontMetrics>>,
     /// Bold font metrics for accurate bold text measurement and Unicode encoding.
     /// When set, bold cells can use a dedicated embedded bold font.
     pub bold_font_metrics: Option<Arc<dyn FontMetrics>>,
+    /// Italic font metrics, used for italic cells like `bold_font_metrics`
+    pub italic_font_metrics: Option<Arc<dyn FontMetrics>>,
+    /// Bold italic font metrics, used for bold italic cells like
+    /// `bold_font_metrics`
+    pub bold_italic_font_metrics: Option<Arc<dyn FontMetrics>>,
+    /// Fonts (resource name and font dictionary) added to the resources of
+    /// every page the table is drawn on
+    pub font_resources: Vec<(String, ObjectId)>,
+    /// Embedded fonts the table is drawn with; subset fonts are rewritten with
+    /// the glyphs drawn once the table is drawn
+    #[cfg(feature = "ttf-parser")]
+    pub(crate) embedded_fonts: Vec<EmbeddedFont>,
+    /// Rows generated at each page break when paginating (e.g. running subtotals)
+    pub page_break_rows: Option<Arc<dyn PageBreakRows>>,
+    /// Creates the pages the table continues on when paginating
+    pub page_factory: Option<Arc<dyn PageFactory>>,
+    /// Draws headers, footers or page numbers on every page when paginating
+    pub page_decorator: Option<Arc<dyn PageDecorator>>,
 }
 
 impl std::fmt::Debug for Table {
@@ -184,11 +249,34 @@ impl std::fmt::Debug for Table {
             .field("column_widths", &self.column_widths)
             .field("total_width", &self.total_width)
             .field("header_rows", &self.header_rows)
+            .field("footer_rows", &self.footer_rows)
+            .field("final_footer_rows", &self.final_footer_rows)
+            .field("keep_together", &self.keep_together)
+            .field("max_rows_per_page", &self.max_rows_per_page)
+            .field("page_fill", &self.page_fill)
             .field("font_metrics", &self.font_metrics.as_ref().map(|_| "..."))
             .field(
                 "bold_font_metrics",
                 &self.bold_font_metrics.as_ref().map(|_| "..."),
             )
+            .field(
+                "italic_font_metrics",
+                &self.italic_font_metrics.as_ref().map(|_| "..."),
+            )
+            .field(
+                "bold_italic_font_metrics",
+                &self.bold_italic_font_metrics.as_ref().map(|_| "..."),
+            )
+            .field("font_resources", &self.font_resources)
+            .field(
+                "page_break_rows",
+                &self.page_break_rows.as_ref().map(|_| "..."),
+            )
+            .field("page_factory", &self.page_factory.as_ref().map(|_| "..."))
+            .field(
+                "page_decorator",
+                &self.page_decorator.as_ref().map(|_| "..."),
+            )
             .finish()
     }
 }
@@ -202,8 +290,21 @@ impl Table {
             column_widths: None,
             total_width: None,
             header_rows: 0,
+            footer_rows: 0,
+            final_footer_rows: 0,
+            keep_together: Vec::new(),
+            max_rows_per_page: None,
+            page_fill: None,
             font_metrics: None,
             bold_font_metrics: None,
+            italic_font_metrics: None,
+            bold_italic_font_metrics: None,
+            font_resources: Vec::new(),
+            #[cfg(feature = "ttf-parser")]
+            embedded_fonts: Vec::new(),
+            page_break_rows: None,
+            page_factory: None,
+            page_decorator: None,
         }
     }
 
@@ -214,9 +315,34 @@ impl Table {
         self
     }
 
-    /// Set the table style
+    /// Set the table style.
+    ///
+    /// Embedded fonts set with [`with_embedded_font`](Self::with_embedded_font)
+    /// and its variants are kept unless the style names its own.
     pub fn with_style(mut self, style: TableStyle) -> Self {
-        self.style = style;
+        let previous = std::mem::replace(&mut self.style, style);
+        for (name, previous) in [
+            (
+                &mut self.style.embedded_font_resource_name,
+                previous.embedded_font_resource_name,
+            ),
+            (
+                &mut self.style.embedded_font_resource_name_bold,
+                previous.embedded_font_resource_name_bold,
+            ),
+            (
+                &mut self.style.embedded_font_resource_name_italic,
+                previous.embedded_font_resource_name_italic,
+            ),
+            (
+                &mut self.style.embedded_font_resource_name_bold_italic,
+                previous.embedded_font_resource_name_bold_italic,
+            ),
+        ] {
+            if name.is_none() {
+                *name = previous;
+            }
+        }
         self
     }
 
@@ -226,7 +352,7 @@ impl Table {
         self
     }
 
-    /// Set total table width
+    /// Set total table width, which the column width minimums must fit in
     pub fn with_total_width(mut self, width: f32) -> Self {
         self.total_width = Some(width);
         self
@@ -250,6 +376,36 @@ impl Table {
         self
     }
 
+    /// Set the number of trailing rows to repeat as a footer on each page
+    pub fn with_footer_rows(mut self, count: usize) -> Self {
+        self.footer_rows = count;
+        self
+    }
+
+    /// Set the number of rows before the footer that only close the final page
+    pub fn with_final_footer_rows(mut self, count: usize) -> Self {
+        self.final_footer_rows = count;
+        self
+    }
+
+    /// Keep a range of rows together on one page when paginating
+    pub fn with_keep_together(mut self, rows: Range<usize>) -> Self {
+        self.keep_together.push(rows);
+        self
+    }
+
+    /// Limit the number of data rows placed on each page when paginating
+    pub fn with_max_rows_per_page(mut self, rows: usize) -> Self {
+        self.max_rows_per_page = Some(rows);
+        self
+    }
+
+    /// Fill the remaining space of the last page when paginating
+    pub fn with_page_fill(mut self, fill: PageFill) -> Self {
+        self.page_fill = Some(fill);
+        self
+    }
+
     /// Set font metrics for accurate text measurement and Unicode encoding.
     ///
     /// When font metrics are provided along with `embedded_font_resource_name`
@@ -270,6 +426,113 @@ impl Table {
         self
     }
 
+    /// Set italic font metrics for italic cells, used with
+    /// `embedded_font_resource_name_italic` like the bold font metrics
+    pub fn with_italic_font_metrics(mut self, metrics: impl FontMetrics + 'static) -> Self {
+        self.italic_font_metrics = Some(Arc::new(metrics));
+        self
+    }
+
+    /// Set bold italic font metrics for bold italic cells, used with
+    /// `embedded_font_resource_name_bold_italic` like the bold font metrics
+    pub fn with_bold_italic_font_metrics(mut self, metrics: impl FontMetrics + 'static) -> Self {
+        self.bold_italic_font_metrics = Some(Arc::new(metrics));
+        self
+    }
+
+    /// Font metrics for bold and/or italic text, if the table has metrics
+    /// for it (see [`font_variant`](crate::style::font_variant))
+    pub(crate) fn font_metrics_for(&self, bold: bool, italic: bool) -> Option<&dyn FontMetrics> {
+        font_variant(
+            bold,
+            italic,
+            [
+                self.font_metrics.as_deref(),
+                self.bold_font_metrics.as_deref(),
+                self.italic_font_metrics.as_deref(),
+                self.bold_italic_font_metrics.as_deref(),
+            ],
+        )
+    }
+
+    /// Add a font to the resources of every page the table is drawn on
+    pub fn with_font_resource(
+        mut self,
+        resource_name: impl Into<String>,
+        font_id: ObjectId,
+    ) -> Self {
+        self.font_resources.push((resource_name.into(), font_id));
+        self
+    }
+
+    /// Draw text with an embedded TrueType font.
+    ///
+    /// Sets the font metrics and the style's `embedded_font_resource_name`,
+    /// and adds the font to the resources of the pages drawn on. Subset fonts
+    /// are rewritten with the glyphs drawn so far once the table is drawn.
+    #[cfg(feature = "ttf-parser")]
+    pub fn with_embedded_font(mut self, font: &EmbeddedFont) -> Self {
+        self.font_metrics = Some(Arc::new(font.clone()));
+        self.style.embedded_font_resource_name = Some(font.resource_name().to_string());
+        self.with_embedded_font_resource(font)
+    }
+
+    /// Draw bold text with an embedded TrueType font (see
+    /// [`with_embedded_font`](Self::with_embedded_font))
+    #[cfg(feature = "ttf-parser")]
+    pub fn with_bold_embedded_font(mut self, font: &EmbeddedFont) -> Self {
+        self.bold_font_metrics = Some(Arc::new(font.clone()));
+        self.style.embedded_font_resource_name_bold = Some(font.resource_name().to_string());
+        self.with_embedded_font_resource(font)
+    }
+
+    /// Draw italic text with an embedded TrueType font (see
+    /// [`with_embedded_font`](Self::with_embedded_font))
+    #[cfg(feature = "ttf-parser")]
+    pub fn with_italic_embedded_font(mut self, font: &EmbeddedFont) -> Self {
+        self.italic_font_metrics = Some(Arc::new(font.clone()));
+        self.style.embedded_font_resource_name_italic = Some(font.resource_name().to_string());
+        self.with_embedded_font_resource(font)
+    }
+
+    /// Draw bold italic text with an embedded TrueType font (see
+    /// [`with_embedded_font`](Self::with_embedded_font))
+    #[cfg(feature = "ttf-parser")]
+    pub fn with_bold_italic_embedded_font(mut self, font: &EmbeddedFont) -> Self {
+        self.bold_italic_font_metrics = Some(Arc::new(font.clone()));
+        self.style.embedded_font_resource_name_bold_italic = Some(font.resource_name().to_string());
+        self.with_embedded_font_resource(font)
+    }
+
+    #[cfg(feature = "ttf-parser")]
+    fn with_embedded_font_resource(mut self, font: &EmbeddedFont) -> Self {
+        self.embedded_fonts.push(font.clone());
+        self.with_font_resource(font.resource_name(), font.font_id())
+    }
+
+    /// Set the rows generated at each page break when paginating.
+    ///
+    /// Use [`RunningSubtotals`](crate::page_break::RunningSubtotals) for
+    /// "carried forward" / "brought forward" totals of numeric columns.
+    pub fn with_page_break_rows(mut self, rows: impl PageBreakRows + 'static) -> Self {
+        self.page_break_rows = Some(Arc::new(rows));
+        self
+    }
+
+    /// Set the factory creating the pages the table continues on when paginating
+    pub fn with_page_factory(mut self, factory: impl PageFactory + 'static) -> Self {
+        self.page_factory = Some(Arc::new(factory));
+        self
+    }
+
+    /// Set the decorator drawing on every page when paginating.
+    ///
+    /// Use [`PageNumbers`](crate::page::PageNumbers) for "Page X of Y" numbers.
+    pub fn with_page_decorator(mut self, decorator: impl PageDecorator + 'static) -> Self {
+        self.page_decorator = Some(Arc::new(decorator));
+        self
+    }
+
     /// Get the number of columns (based on the first row, accounting for colspan)
     pub fn column_count(&self) -> usize {
         self.rows
@@ -280,6 +543,15 @@ impl Table {
 
     /// Validate table structure
     pub fn validate(&self) -> Result<()> {
+        self.validated_grid().map(|_| ())
+    }
+
+    /// Validate table structure and return the resolved cell grid.
+    ///
+    /// Rows below a cell with `rowspan > 1` declare fewer cells; each row must
+    /// cover every column once cells spanning from earlier rows are counted.
+    /// Column widths must fit in `total_width` when one is set.
+    pub(crate) fn validated_grid(&self) -> Result<CellGrid> {
         if self.rows.is_empty() {
             return Err(crate::error::TableError::InvalidTable(
                 "Table has no rows".to_string(),
@@ -287,19 +559,34 @@ impl Table {
         }
 
         let expected_cols = self.column_count();
-        for (i, row) in self.rows.iter().enumerate() {
-            // Calculate the total column coverage including colspan
-            let mut total_coverage = 0;
-            for cell in &row.cells {
-                total_coverage += cell.colspan.max(1);
-            }
+        let grid = CellGrid::new(self)?;
+
+        let reserved_rows =
+            self.header_rows.min(self.rows.len()) + self.footer_rows + self.final_footer_rows;
+        if reserved_rows > self.rows.len() {
+            return Err(crate::error::TableError::InvalidTable(format!(
+                "Header and footer rows ({}) exceed the number of rows ({})",
+                reserved_rows,
+                self.rows.len()
+            )));
+        }
 
-            if total_coverage != expected_cols {
-                return Err(crate::error::TableError::InvalidTable(format!(
-                    "Row {} covers {} columns (with colspan), expected {}",
-                    i, total_coverage, expected_cols
-                )));
-            }
+        if let Some(group) = self
+            .keep_together
+            .iter()
+            .find(|g| g.start > g.end || g.end > self.rows.len())
+        {
+            return Err(crate::error::TableError::InvalidTable(format!(
+                "Keep-together rows {:?} are not a range of the table's {} rows",
+                group,
+                self.rows.len()
+            )));
+        }
+
+        if self.max_rows_per_page == Some(0) {
+            return Err(crate::error::TableError::InvalidTable(
+                "Maximum rows per page must be at least 1".to_string(),
+            ));
         }
 
         if let Some(ref widths) = self.column_widths {
@@ -326,9 +613,55 @@ impl Table {
                     total_percentage
                 )));
             }
+
+            for (col, width) in widths.iter().enumerate() {
+                let valid = match width {
+                    ColumnWidth::Pixels(w) | ColumnWidth::Percentage(w) => {
+                        w.is_finite() && *w >= 0.0
+                    }
+                    ColumnWidth::Fraction(weight) => weight.is_finite() && *weight > 0.0,
+                    ColumnWidth::MinMax {
+                        min,
+                        max,
+                        preferred,
+                    } => {
+                        min.is_finite()
+                            && max.is_finite()
+                            && *min >= 0.0
+                            && min <= max
+                            && preferred.is_none_or(|p| p.is_finite() && (*min..=*max).contains(&p))
+                    }
+                    ColumnWidth::Auto => true,
+                };
+                if !valid {
+                    return Err(crate::error::TableError::InvalidTable(format!(
+                        "Column {} has an invalid width constraint {:?}",
+                        col, width
+                    )));
+                }
+            }
+
+            // Every column must be able to reach its lower bound within the table width
+            if let Some(total_width) = self.total_width {
+                let required: f32 = widths
+                    .iter()
+                    .map(|width| match width {
+                        ColumnWidth::Pixels(w) => *w,
+                        ColumnWidth::Percentage(p) => total_width * p / 100.0,
+                        ColumnWidth::MinMax { min, .. } => *min,
+                        ColumnWidth::Auto | ColumnWidth::Fraction(_) => MIN_COLUMN_WIDTH,
+                    })
+                    .sum();
+                if required > total_width + 0.01 {
+                    return Err(crate::error::TableError::InvalidTable(format!(
+                        "Column width constraints need at least {:.1}pt, but the table width is {:.1}pt",
+                        required, total_width
+                    )));
+                }
+            }
         }
 
-        Ok(())
+        Ok(grid)
     }
 }
 
@@ -345,6 +678,11 @@ pub struct Row {
     pub style: Option<RowStyle>,
     /// Explicit height (if None, auto-calculate)
     pub height: Option<f32>,
+    /// Keep this row on the same page as the next one when paginating
+    /// (e.g. a section heading)
+    pub keep_with_next: bool,
+    /// Start a new page before this row when paginating
+    pub page_break_before: bool,
 }
 
 impl Row {
@@ -354,6 +692,8 @@ impl Row {
             cells,
             style: None,
             height: None,
+            keep_with_next: false,
+            page_break_before: false,
         }
     }
 
@@ -368,6 +708,18 @@ impl Row {
         self.height = Some(height);
         self
     }
+
+    /// Start a new page before this row when paginating
+    pub fn with_page_break_before(mut self, page_break: bool) -> Self {
+        self.page_break_before = page_break;
+        self
+    }
+
+    /// Keep this row on the same page as the next one when paginating
+    pub fn with_keep_with_next(mut self, keep: bool) -> Self {
+        self.keep_with_next = keep;
+        self
+    }
 }
 
 /// Represents a cell in a table
@@ -502,6 +854,126 @@ mod tests {
         assert!(table.validate().is_err());
     }
 
+    #[test]
+    fn test_table_validation_with_rowspan() {
+        let table = Table::new()
+            .add_row(Row::new(vec![
+                Cell::new("A").with_rowspan(2),
+                Cell::new("B"),
+            ]))
+            .add_row(Row::new(vec![Cell::new("C")]));
+        assert!(table.validate().is_ok());
+
+        let table = table.add_row(Row::new(vec![Cell::new("D")]));
+        assert!(table.validate().is_err());
+    }
+
+    #[test]
+    fn test_unsatisfiable_column_constraints_are_rejected() {
+        let row = Row::new(vec![Cell::new("A"), Cell::new("B")]);
+
+        let inverted = Table::new()
+            .with_column_widths(vec![
+                ColumnWidth::MinMax {
+                    min: 80.0,
+                    max: 40.0,
+                    preferred: None,
+                },
+                ColumnWidth::Auto,
+            ])
+            .add_row(row.clone());
+        assert!(inverted.validate().is_err());
+
+        let preferred_outside = Table::new()
+            .with_column_widths(vec![
+                ColumnWidth::MinMax {
+                    min: 40.0,
+                    max: 80.0,
+                    preferred: Some(120.0),
+                },
+                ColumnWidth::Auto,
+            ])
+            .add_row(row.clone());
+        assert!(preferred_outside.validate().is_err());
+
+        let too_wide = Table::new()
+            .with_total_width(200.0)
+            .with_column_widths(vec![
+                ColumnWidth::Pixels(150.0),
+                ColumnWidth::MinMax {
+                    min: 80.0,
+                    max: 120.0,
+                    preferred: None,
+                },
+            ])
+            .add_row(row.clone());
+        assert!(too_wide.validate().is_err());
+
+        let fits = Table::new()
+            .with_total_width(200.0)
+            .with_column_widths(vec![ColumnWidth::Pixels(150.0), ColumnWidth::Fraction(1.0)])
+            .add_row(row);
+        assert!(fits.validate().is_ok());
+    }
+
+    #[test]
+    fn test_constraints_wider_than_page_overflow() {
+        let table = Table::new()
+            .with_pixel_widths(vec![400.0, 300.0])
+            .add_row(Row::new(vec![Cell::new("A"), Cell::new("B")]));
+
+        // Only an explicit table width is enforced; the page width is not
+        assert!(table.validate().is_ok());
+        let layout = crate::layout::calculate_layout(&table).unwrap();
+        assert_eq!(layout.column_widths, vec![400.0, 300.0]);
+        assert!(table.clone().with_total_width(600.0).validate().is_err());
+    }
+
+    #[test]
+    fn test_non_finite_column_widths_are_rejected() {
+        let row = Row::new(vec![Cell::new("A"), Cell::new("B")]);
+        let widths = [
+            ColumnWidth::MinMax {
+                min: f32::NAN,
+                max: 100.0,
+                preferred: None,
+            },
+            ColumnWidth::MinMax {
+                min: 10.0,
+                max: f32::NAN,
+                preferred: None,
+            },
+            ColumnWidth::MinMax {
+                min: 10.0,
+                max: 100.0,
+                preferred: Some(f32::NAN),
+            },
+            ColumnWidth::Pixels(f32::INFINITY),
+            ColumnWidth::Fraction(f32::NAN),
+        ];
+
+        for width in widths {
+            let table = Table::new()
+                .with_column_widths(vec![width.clone(), ColumnWidth::Auto])
+                .add_row(row.clone());
+            assert!(table.validate().is_err(), "{width:?} should be rejected");
+            assert!(crate::layout::calculate_layout(&table).is_err());
+        }
+    }
+
+    #[test]
+    fn test_keep_together_must_be_a_range_of_rows() {
+        let mut table = Table::new();
+        for text in ["A", "B", "C"] {
+            table = table.add_row(Row::new(vec![Cell::new(text)]));
+        }
+        assert!(table.clone().with_keep_together(0..3).validate().is_ok());
+        assert!(table.clone().with_keep_together(0..4).validate().is_err());
+
+        let inverted = Range { start: 2, end: 1 };
+        assert!(table.with_keep_together(inverted).validate().is_err());
+    }
+
     #[test]
     fn test_cell_builder() {
         let cell = Cell::new("Test")
@@ -0,0 +1,206 @@
+//! Fixtures and content helpers shared by the unit tests
+
+use crate::Result;
+use crate::page::{ContinuationPage, PageBox, PageFactory, insert_page_after};
+use crate::style::TableStyle;
+use crate::table::{Cell, Row, Table};
+use lopdf::content::{Content, Operation};
+use lopdf::{Document, Object, ObjectId, dictionary};
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct RectExtents {
+    pub(crate) max_top: f32,
+    pub(crate) min_bottom: f32,
+    pub(crate) max_right: f32,
+}
+
+pub(crate) fn object_to_f32(object: &Object) -> Option<f32> {
+    match object {
+        Object::Integer(v) => Some(*v as f32),
+        Object::Real(v) => Some(*v),
+        _ => None,
+    }
+}
+
+pub(crate) fn approx_eq(a: f32, b: f32) -> bool {
+    (a - b).abs() <= 0.001
+}
+
+pub(crate) fn page_content_operations(doc: &Document, page_id: ObjectId) -> Vec<Operation> {
+    let bytes = doc
+        .get_page_content(page_id)
+        .expect("page content should be readable");
+    Content::decode(&bytes)
+        .expect("page content should decode")
+        .operations
+}
+
+pub(crate) fn page_rect_extents(doc: &Document, page_id: ObjectId) -> Option<RectExtents> {
+    let bytes = doc.get_page_content(page_id).ok()?;
+    let content = Content::decode(&bytes).ok()?;
+    let mut max_top = f32::NEG_INFINITY;
+    let mut min_bottom = f32::INFINITY;
+    let mut max_right = f32::NEG_INFINITY;
+    let mut found = false;
+
+    for op in content.operations {
+        if op.operator != "re" || op.operands.len() != 4 {
+            continue;
+        }
+        let x = object_to_f32(&op.operands[0])?;
+        let y = object_to_f32(&op.operands[1])?;
+        let w = object_to_f32(&op.operands[2])?;
+        let h = object_to_f32(&op.operands[3])?;
+        max_top = max_top.max(y + h);
+        min_bottom = min_bottom.min(y);
+        max_right = max_right.max(x + w);
+        found = true;
+    }
+
+    if found {
+        Some(RectExtents {
+            max_top,
+            min_bottom,
+            max_right,
+        })
+    } else {
+        None
+    }
+}
+
+/// DejaVu Sans subset to ASCII, common accented letters, €, Ω and some
+/// punctuation, or `None` when the fixture is not there (e.g. in the
+/// published package, which excludes it)
+#[cfg(feature = "ttf-parser")]
+pub(crate) fn dejavu_sans() -> Option<Vec<u8>> {
+    let path = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/DejaVuSans-Subset.ttf"
+    );
+    std::fs::read(path).ok()
+}
+
+pub(crate) fn make_test_doc() -> (Document, ObjectId) {
+    let mut doc = Document::with_version("1.7");
+    let pages_id = doc.add_object(dictionary! {
+        "Type" => "Pages",
+        "Kids" => vec![],
+        "Count" => 0,
+    });
+    let page_id = doc.add_object(dictionary! {
+        "Type" => "Page",
+        "Parent" => pages_id,
+        "MediaBox" => vec![0.into(), 0.into(), 595.into(), 842.into()],
+    });
+    if let Ok(Object::Dictionary(pages)) = doc.get_object_mut(pages_id) {
+        if let Ok(Object::Array(kids)) = pages.get_mut(b"Kids") {
+            kids.push(page_id.into());
+        }
+        pages.set("Count", Object::Integer(1));
+    }
+    let font_id = doc.add_object(dictionary! {
+        "Type" => "Font",
+        "Subtype" => "Type1",
+        "BaseFont" => "Helvetica",
+    });
+    let resources_id = doc.add_object(dictionary! {
+        "Font" => dictionary! { "F1" => font_id },
+    });
+    if let Ok(Object::Dictionary(page)) = doc.get_object_mut(page_id) {
+        page.set("Resources", resources_id);
+    }
+    let catalog_id = doc.add_object(dictionary! {
+        "Type" => "Catalog",
+        "Pages" => pages_id,
+    });
+    doc.trailer.set("Root", catalog_id);
+    (doc, page_id)
+}
+
+pub(crate) fn page_shows_text(doc: &Document, page_id: ObjectId, text: &str) -> bool {
+    page_content_operations(doc, page_id).iter().any(|op| {
+        op.operator == "Tj"
+            && op.operands.first().and_then(|o| o.as_str().ok()) == Some(text.as_bytes())
+    })
+}
+
+pub(crate) fn page_texts(doc: &Document, page_id: ObjectId) -> Vec<String> {
+    page_content_operations(doc, page_id)
+        .iter()
+        .filter(|op| op.operator == "Tj")
+        .filter_map(|op| op.operands.first()?.as_str().ok())
+        .map(|bytes| String::from_utf8_lossy(bytes).into_owned())
+        .collect()
+}
+
+pub(crate) fn page_tree_order(doc: &Document) -> Vec<ObjectId> {
+    doc.get_pages().into_values().collect()
+}
+
+pub(crate) fn numbered_rows_table(rows: usize) -> Table {
+    let style = TableStyle {
+        page_height: Some(842.0),
+        ..Default::default()
+    };
+    let mut table = Table::new()
+        .with_style(style)
+        .with_pixel_widths(vec![200.0]);
+    for row in 0..rows {
+        table = table.add_row(Row::new(vec![Cell::new(format!("item-{row}"))]).with_height(30.0));
+    }
+    table
+}
+
+pub(crate) fn with_header_row(mut table: Table) -> Table {
+    table
+        .rows
+        .insert(0, Row::new(vec![Cell::new("Header")]).with_height(30.0));
+    table.header_rows = 1;
+    table
+}
+
+/// Appends letterhead pages with a fixed usable region
+pub(crate) struct LetterheadPages {
+    pub(crate) indexes: std::sync::Mutex<Vec<usize>>,
+}
+
+impl PageFactory for LetterheadPages {
+    fn continuation_page(
+        &self,
+        doc: &mut Document,
+        previous_page_id: ObjectId,
+        page_index: usize,
+    ) -> Result<ContinuationPage> {
+        self.indexes.lock().unwrap().push(page_index);
+        let page_id = insert_page_after(
+            doc,
+            previous_page_id,
+            dictionary! {
+                "Type" => "Page",
+                "MediaBox" => vec![0.into(), 0.into(), 500.into(), 700.into()],
+            },
+        )?;
+
+        let mut letterhead = Content {
+            operations: vec![Operation::new(
+                "re",
+                vec![0.into(), 650.into(), 500.into(), 50.into()],
+            )],
+        }
+        .encode()?;
+        // Separate from the table's content stream when they are concatenated
+        letterhead.push(b'\n');
+        doc.add_page_contents(page_id, letterhead)?;
+
+        Ok(ContinuationPage {
+            page_id,
+            region: PageBox {
+                left: 30.0,
+                bottom: 80.0,
+                right: 470.0,
+                top: 600.0,
+                rotation: 0,
+            },
+        })
+    }
+}
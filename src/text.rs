@@ -1,116 +1,9 @@
 //! Text handling and wrapping utilities
 
-use crate::constants::*;
 use tracing::trace;
 
-/// Break text into lines that fit within the specified width
-pub fn wrap_text(text: &str, max_width: f32, font_size: f32) -> Vec<String> {
-    if text.is_empty() {
-        return vec![String::new()];
-    }
-
-    let char_width = font_size * DEFAULT_CHAR_WIDTH_RATIO; // Simplified character width estimation
-    let max_chars_per_line = (max_width / char_width) as usize;
-
-    if max_chars_per_line == 0 {
-        return vec![text.to_string()];
-    }
-
-    let mut all_lines = Vec::new();
-
-    // First, split by explicit newlines to preserve line breaks
-    let segments: Vec<&str> = text.split('\n').collect();
-
-    for segment in segments {
-        // For empty segments (from consecutive newlines), add an empty line
-        if segment.is_empty() {
-            all_lines.push(String::new());
-            continue;
-        }
-
-        // Now wrap each segment that's too long
-        let words: Vec<&str> = segment.split_whitespace().collect();
-
-        if words.is_empty() {
-            // Segment has only whitespace, preserve it as empty line
-            all_lines.push(String::new());
-            continue;
-        }
-
-        let mut current_line = String::new();
-        let mut current_length = 0;
-
-        for word in words {
-            let word_length = word.chars().count();
-
-            // Check if adding this word would exceed the line width
-            if current_length > 0 && current_length + 1 + word_length > max_chars_per_line {
-                // Start a new line
-                all_lines.push(current_line.trim().to_string());
-                current_line = word.to_string();
-                current_length = word_length;
-            } else {
-                // Add word to current line
-                if !current_line.is_empty() {
-                    current_line.push(' ');
-                    current_length += 1;
-                }
-                current_line.push_str(word);
-                current_length += word_length;
-            }
-
-            // Handle very long words that don't fit on a single line
-            if word_length > max_chars_per_line {
-                // Break the word using char_indices to avoid splitting multi-byte characters
-                let mut remaining = word;
-                while remaining.chars().count() > max_chars_per_line {
-                    let split_byte = remaining
-                        .char_indices()
-                        .nth(max_chars_per_line)
-                        .map(|(idx, _)| idx)
-                        .unwrap_or(remaining.len());
-                    let (chunk, rest) = remaining.split_at(split_byte);
-                    all_lines.push(chunk.to_string());
-                    remaining = rest;
-                }
-                if !remaining.is_empty() {
-                    current_line = remaining.to_string();
-                    current_length = remaining.chars().count();
-                }
-            }
-        }
-
-        // Add the last line of this segment if not empty
-        if !current_line.trim().is_empty() {
-            all_lines.push(current_line.trim().to_string());
-        }
-    }
-
-    // If we ended up with no lines (shouldn't happen), return at least one empty line
-    if all_lines.is_empty() {
-        all_lines.push(String::new());
-    }
-
-    trace!("Wrapped text into {} lines", all_lines.len());
-    all_lines
-}
-
-/// Calculate the height needed for wrapped text
-pub fn calculate_wrapped_text_height(
-    text: &str,
-    max_width: f32,
-    font_size: f32,
-    line_spacing: f32,
-) -> f32 {
-    let lines = wrap_text(text, max_width, font_size);
-    let line_height = font_size * line_spacing;
-    lines.len() as f32 * line_height
-}
-
-/// Break text into lines using actual font metrics for width measurement.
-///
-/// Same logic as `wrap_text` but uses `FontMetrics::text_width()` for accurate
-/// measurement instead of the fixed `DEFAULT_CHAR_WIDTH_RATIO` heuristic.
+/// Break text into lines that fit within the specified width, measured with
+/// `FontMetrics::text_width()`.
 pub fn wrap_text_with_metrics(
     text: &str,
     max_width: f32,
@@ -203,22 +96,16 @@ pub fn wrap_text_with_metrics(
     all_lines
 }
 
-/// Calculate the height needed for wrapped text using font metrics
-pub fn calculate_wrapped_text_height_with_metrics(
-    text: &str,
-    max_width: f32,
-    font_size: f32,
-    line_spacing: f32,
-    metrics: &dyn crate::font::FontMetrics,
-) -> f32 {
-    let lines = wrap_text_with_metrics(text, max_width, font_size, metrics);
-    let line_height = font_size * line_spacing;
-    lines.len() as f32 * line_height
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::font::FontMetrics;
+    use crate::standard_fonts::StandardFont;
+
+    /// Wrap with Courier, whose characters are all 0.6em wide
+    fn wrap_text(text: &str, max_width: f32, font_size: f32) -> Vec<String> {
+        wrap_text_with_metrics(text, max_width, font_size, &StandardFont::Courier)
+    }
 
     #[test]
     fn test_wrap_text() {
@@ -283,11 +170,11 @@ mod tests {
     }
 
     #[test]
-    fn test_text_height_with_newlines() {
+    fn test_text_lines_with_newlines() {
         let text = "Line 1\nLine 2\nLine 3";
-        let height = calculate_wrapped_text_height(text, 200.0, 10.0, 1.2);
-        // 3 lines * 10.0 font size * 1.2 line spacing = 36.0
-        assert_eq!(height, 36.0);
+        let lines = wrap_text(text, 200.0, 10.0);
+        assert_eq!(lines, vec!["Line 1", "Line 2", "Line 3"]);
+        assert_eq!(StandardFont::Courier.text_width(&lines[0], 10.0), 36.0);
     }
 
     #[test]
@@ -322,8 +209,8 @@ mod tests {
         assert_eq!(text.len(), 8); // 8 bytes
         assert_eq!(text.chars().count(), 4); // 4 characters
 
-        // With font_size=10 and ratio=0.5, char_width=5, max_chars_per_line=20/5=4
-        let lines = wrap_text(text, 20.0, 10.0);
+        // With font_size=10 each char is 6pt wide, so 4 chars fit in 24pt
+        let lines = wrap_text(text, 24.0, 10.0);
         // All 4 chars should fit on one line
         assert_eq!(lines.len(), 1);
         assert_eq!(lines[0], text);